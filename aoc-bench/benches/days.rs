@@ -0,0 +1,390 @@
+use aoc_bench::{inputs, Bencher, Rng};
+use aoc_core::{blocks, parse_numbers, ParseError};
+use aoc_grid::Grid;
+
+fn main() {
+    let mut bencher = Bencher::from_args();
+    dec01(&mut bencher);
+    dec02(&mut bencher);
+    dec03(&mut bencher);
+    dec04(&mut bencher);
+    dec05(&mut bencher);
+    dec06(&mut bencher);
+    dec07(&mut bencher);
+    dec08(&mut bencher);
+    dec09(&mut bencher);
+    dec10(&mut bencher);
+    dec11(&mut bencher);
+    dec13(&mut bencher);
+    dec14(&mut bencher);
+    dec15(&mut bencher);
+    bencher.finish();
+}
+
+fn lines<T>(input: &str, parse: impl Fn(&str) -> Result<T, ParseError>) -> Vec<T> {
+    input.lines().map(|line| parse(line).unwrap()).collect()
+}
+
+fn dec01(bencher: &mut Bencher) {
+    for count in [1_000, 10_000, 1_000_000] {
+        let input = inputs::location_lists(&mut Rng::new(1), count);
+        let pairs: Vec<Vec<i32>> = lines(&input, |line| parse_numbers(line, 1));
+        let left: Vec<i32> = pairs.iter().map(|pair| pair[0]).collect();
+        let right: Vec<i32> = pairs.iter().map(|pair| pair[1]).collect();
+
+        bencher.bench(
+            &format!("dec01/difference_vector/{}", count),
+            || (),
+            |_| dec01::difference_vector(&left, &right),
+        );
+        // quadratic, a million numbers would take forever
+        if count <= 10_000 {
+            bencher.bench(
+                &format!("dec01/similarity_score/{}", count),
+                || (),
+                |_| {
+                    left.iter()
+                        .map(|&number| dec01::similarity_score(number, &right))
+                        .sum::<i32>()
+                },
+            );
+        }
+        bencher.bench(
+            &format!("dec01/total_similarity/{}", count),
+            || (),
+            |_| dec01::total_similarity(left.iter().copied(), right.iter().copied()),
+        );
+    }
+}
+
+fn dec02(bencher: &mut Bencher) {
+    for count in [1_000, 10_000, 100_000] {
+        let input = inputs::reports(&mut Rng::new(2), count, 8);
+        let reports: Vec<Vec<i32>> = lines(&input, |line| parse_numbers(line, 1));
+
+        bencher.bench(
+            &format!("dec02/is_report_safe/{}", count),
+            || (),
+            |_| {
+                reports
+                    .iter()
+                    .filter(|report| dec02::is_report_safe(report))
+                    .count()
+            },
+        );
+    }
+}
+
+fn dec03(bencher: &mut Bencher) {
+    for length in [1_000, 10_000, 100_000] {
+        let memory = inputs::corrupted_memory(&mut Rng::new(3), length);
+
+        let instructions = dec03::InstructionSet::default();
+
+        bencher.bench(
+            &format!("dec03/lexer/{}", length),
+            || (),
+            |_| dec03::Lexer::new(&memory, &instructions).count(),
+        );
+        bencher.bench(
+            &format!("dec03/execute/{}", length),
+            || (),
+            |_| dec03::execute(&memory, &instructions, true),
+        );
+    }
+}
+
+fn dec04(bencher: &mut Bencher) {
+    for size in [10, 100, 300] {
+        let grid: Grid<char> = inputs::word_search(&mut Rng::new(4), size).parse().unwrap();
+
+        let search = dec04::WordSearch::new(&["XMAS"]);
+
+        bencher.bench(
+            &format!("dec04/word_search/{}", size),
+            || (),
+            |_| search.count(&grid),
+        );
+        let x_mas: dec04::Stencil = "M.S/.A./M.S".parse().unwrap();
+
+        bencher.bench(
+            &format!("dec04/stencil/{}", size),
+            || (),
+            |_| x_mas.find(&grid).len(),
+        );
+    }
+}
+
+fn dec05(bencher: &mut Bencher) {
+    for pages in [10, 50, 100] {
+        let input = inputs::print_queue(&mut Rng::new(5), pages, 200, 15);
+        let rules: Vec<dec05::Rule> = input
+            .lines()
+            .filter(|line| line.contains('|'))
+            .map(|line| line.parse().unwrap())
+            .collect();
+        let updates: Vec<Vec<i32>> =
+            lines(input.split("\n\n").nth(1).unwrap(), dec05::parse_update);
+
+        bencher.bench(
+            &format!("dec05/validate_update/{}", pages),
+            || (),
+            |_| {
+                updates
+                    .iter()
+                    .filter(|update| {
+                        let relevant_rules = dec05::filter_relevant_rules(update, &rules);
+                        dec05::validate_update(update, &relevant_rules)
+                    })
+                    .count()
+            },
+        );
+        let rule_set = dec05::RuleSet::new(&rules);
+        bencher.bench(
+            &format!("dec05/rule_set/{}", pages),
+            || (),
+            |_| {
+                updates
+                    .iter()
+                    .filter(|update| rule_set.is_valid(update))
+                    .count()
+            },
+        );
+        bencher.bench(
+            &format!("dec05/reorder_update/{}", pages),
+            || (),
+            |_| {
+                updates
+                    .iter()
+                    .map(|update| {
+                        let relevant_rules = dec05::filter_relevant_rules(update, &rules);
+                        dec05::reorder_update(update, &relevant_rules).unwrap()
+                    })
+                    .count()
+            },
+        );
+    }
+}
+
+fn dec06(bencher: &mut Bencher) {
+    for size in [10, 50, 130] {
+        let map: dec06::Map = inputs::lab(&mut Rng::new(6), size).parse().unwrap();
+        // the same map for every iteration, cloning it would cost more than walking it
+        let mut walking = map.clone();
+        // generated labs can trap the guard, every state is visited at most once before that
+        let steps = 4 * size * size;
+
+        bencher.bench(
+            &format!("dec06/move_player/{}", size),
+            || (),
+            |_| {
+                walking.reset_player();
+                for _ in 0..steps {
+                    if !walking.move_player() {
+                        break;
+                    }
+                }
+                walking.visited_count()
+            },
+        );
+        bencher.bench(
+            &format!("dec06/walk/{}", size),
+            || (),
+            |_| {
+                walking.reset_player();
+                walking.walk()
+            },
+        );
+        bencher.bench(
+            &format!("dec06/jump_walk/{}", size),
+            || (),
+            |_| {
+                walking.reset_player();
+                walking.jump_walk()
+            },
+        );
+
+        // `loop_obstructions` walked one step at a time
+        let mut walked = map.clone();
+        walked.walk();
+        let path = walked.visited();
+        bencher.bench(
+            &format!("dec06/obstructions_step/{}", size),
+            || (),
+            |_| {
+                path.iter()
+                    .filter(|&&point| {
+                        if !walking.add_obstacle(point) {
+                            return false;
+                        }
+                        walking.reset_player();
+                        let outcome = walking.walk();
+                        walking.remove_obstacle(point);
+                        outcome == dec06::WalkOutcome::Looped
+                    })
+                    .count()
+            },
+        );
+        bencher.bench(
+            &format!("dec06/loop_obstructions/{}", size),
+            || (),
+            |_| dec06::loop_obstructions(&map).len(),
+        );
+    }
+}
+
+fn dec07(bencher: &mut Bencher) {
+    for operands in [3, 6, 9] {
+        let input = inputs::equations(&mut Rng::new(7), 20, operands);
+        let equations: Vec<dec07::Equation> = lines(&input, str::parse);
+
+        for concatenation in [false, true] {
+            let part = if concatenation {
+                "with_concatenation"
+            } else {
+                "check"
+            };
+            bencher.bench(
+                &format!("dec07/{}/{}", part, operands),
+                || (),
+                |_| {
+                    equations
+                        .iter()
+                        .filter(|equation| equation.check(concatenation))
+                        .count()
+                },
+            );
+        }
+    }
+}
+
+fn dec08(bencher: &mut Bencher) {
+    for size in [12, 50, 200] {
+        let map: dec08::AntennaMap = inputs::antenna_map(&mut Rng::new(8), size, size)
+            .parse()
+            .unwrap();
+
+        bencher.bench(
+            &format!("dec08/antinodes/{}", size),
+            || (),
+            |_| map.antinodes(),
+        );
+    }
+}
+
+fn dec09(bencher: &mut Bencher) {
+    for length in [100, 1_000, 5_000] {
+        let disk: dec09::Disk = inputs::disk_map(&mut Rng::new(9), length).parse().unwrap();
+
+        bencher.bench(
+            &format!("dec09/compact/{}", length),
+            || disk.clone(),
+            |mut disk| {
+                disk.compact();
+                disk.checksum()
+            },
+        );
+        bencher.bench(
+            &format!("dec09/compact_files/{}", length),
+            || disk.clone(),
+            |mut disk| {
+                disk.compact_files();
+                disk.checksum()
+            },
+        );
+    }
+}
+
+fn dec10(bencher: &mut Bencher) {
+    for size in [10, 50, 100] {
+        let map: dec10::Map = inputs::topographic_map(&mut Rng::new(10), size)
+            .parse()
+            .unwrap();
+
+        bencher.bench(&format!("dec10/score/{}", size), || (), |_| map.score());
+        bencher.bench(&format!("dec10/rating/{}", size), || (), |_| map.rating());
+    }
+}
+
+fn dec11(bencher: &mut Bencher) {
+    let input = inputs::stones(&mut Rng::new(11), 8);
+    let stones: Vec<i64> = parse_numbers(input.trim_end(), 1).unwrap();
+
+    for blinks in [25, 50, 75] {
+        bencher.bench(
+            &format!("dec11/blink_efficiently/{}", blinks),
+            || (),
+            |_| dec11::blink_efficiently(&stones, blinks),
+        );
+    }
+}
+
+fn dec13(bencher: &mut Bencher) {
+    for count in [10, 100, 1_000] {
+        let input = inputs::claw_machines(&mut Rng::new(13), count);
+        let machines: Vec<dec13::ClawMachine> = blocks(&input)
+            .map(|(_, block)| dec13::build_machine(block.lines().collect(), false).unwrap())
+            .collect();
+
+        bencher.bench(
+            &format!("dec13/win_prize/{}", count),
+            || (),
+            |_| machines.iter().filter_map(dec13::win_prize).count(),
+        );
+        bencher.bench(
+            &format!("dec13/win_prize_efficiently/{}", count),
+            || (),
+            |_| {
+                machines
+                    .iter()
+                    .filter_map(dec13::win_prize_efficiently)
+                    .count()
+            },
+        );
+    }
+}
+
+fn dec14(bencher: &mut Bencher) {
+    let map = dec14::Map::new(101, 103);
+    let input = inputs::robots(&mut Rng::new(14), 500, 101, 103);
+    let robots: Vec<dec14::Robot> = lines(&input, str::parse);
+
+    for seconds in [100, 1_000, 10_000] {
+        bencher.bench(
+            &format!("dec14/travel/{}", seconds),
+            || robots.clone(),
+            |mut robots| {
+                for _ in 0..seconds {
+                    for robot in &mut robots {
+                        robot.travel(&map);
+                    }
+                }
+                dec14::count_by_quadrant(&robots, &map)
+            },
+        );
+    }
+}
+
+fn dec15(bencher: &mut Bencher) {
+    for size in [10, 50, 100] {
+        let input = inputs::warehouse(&mut Rng::new(15), size, 20 * size);
+        let (warehouse, movements) = input.split_once("\n\n").unwrap();
+        let warehouse: dec15::Warehouse = warehouse.parse().unwrap();
+        let movements: Vec<dec15::Movement> = movements
+            .chars()
+            .filter(|&ch| ch != '\n')
+            .map(|ch| dec15::Movement::try_from(ch).unwrap())
+            .collect();
+
+        bencher.bench(
+            &format!("dec15/move_robot/{}", size),
+            || warehouse.clone(),
+            |mut warehouse| {
+                for movement in &movements {
+                    warehouse.move_robot(movement);
+                }
+                warehouse.gps_sum()
+            },
+        );
+    }
+}
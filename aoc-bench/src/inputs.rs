@@ -0,0 +1,235 @@
+// puzzle inputs of any size, in the same format as the real ones
+use std::fmt::Write;
+
+use crate::Rng;
+
+fn grid(size: usize, mut cell: impl FnMut(usize, usize) -> char) -> String {
+    let mut grid = String::new();
+    for y in 0..size {
+        grid.extend((0..size).map(|x| cell(x, y)));
+        grid.push('\n');
+    }
+    grid
+}
+
+pub fn location_lists(rng: &mut Rng, count: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..count {
+        let _ = writeln!(
+            input,
+            "{}   {}",
+            rng.range(10000, 100000),
+            rng.range(10000, 100000)
+        );
+    }
+    input
+}
+
+pub fn reports(rng: &mut Rng, count: usize, levels: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..count {
+        let direction = rng.pick(&[-1, 1]);
+        let mut level = rng.range(20, 80);
+        let report: Vec<String> = (0..levels)
+            .map(|_| {
+                // mostly safe steps, with the odd jump thrown in
+                level += direction * if rng.chance(5) { 5 } else { rng.range(1, 4) };
+                level.to_string()
+            })
+            .collect();
+        let _ = writeln!(input, "{}", report.join(" "));
+    }
+    input
+}
+
+pub fn corrupted_memory(rng: &mut Rng, length: usize) -> String {
+    let mut input = String::new();
+    while input.len() < length {
+        match rng.range(0, 10) {
+            0..=3 => {
+                let _ = write!(input, "mul({},{})", rng.range(1, 1000), rng.range(1, 1000));
+            }
+            4 => input.push_str("do()"),
+            5 => input.push_str("don't()"),
+            6 => {
+                let _ = write!(input, "mul[{},{})", rng.range(1, 1000), rng.range(1, 1000));
+            }
+            _ => input.push(rng.pick(&['x', '%', '&', '@', ' ', ')', '(', 'm', 'u', '\n'])),
+        }
+    }
+    input
+}
+
+pub fn word_search(rng: &mut Rng, size: usize) -> String {
+    grid(size, |_, _| rng.pick(&['X', 'M', 'A', 'S']))
+}
+
+// rules for every pair of pages in the order of their numbers, updates in any order
+pub fn print_queue(rng: &mut Rng, pages: usize, updates: usize, length: usize) -> String {
+    let numbers: Vec<i64> = (0..pages as i64).map(|n| 10 + n).collect();
+    let mut input = String::new();
+    for (i, front) in numbers.iter().enumerate() {
+        for back in &numbers[i + 1..] {
+            let _ = writeln!(input, "{}|{}", front, back);
+        }
+    }
+    input.push('\n');
+    for _ in 0..updates {
+        let update: Vec<String> = (0..length)
+            .map(|_| rng.pick(&numbers).to_string())
+            .collect();
+        let _ = writeln!(input, "{}", update.join(","));
+    }
+    input
+}
+
+pub fn lab(rng: &mut Rng, size: usize) -> String {
+    let guard = (size / 2, size / 2);
+    grid(size, |x, y| match (x, y) {
+        _ if (x, y) == guard => '^',
+        _ if rng.chance(4) => '#',
+        _ => '.',
+    })
+}
+
+// half of the equations can be solved, the other half has to be searched exhaustively
+pub fn equations(rng: &mut Rng, count: usize, operands: usize) -> String {
+    let mut input = String::new();
+    for i in 0..count {
+        let numbers: Vec<i64> = (0..operands).map(|_| rng.range(1, 10)).collect();
+        let mut result = numbers[0];
+        for number in &numbers[1..] {
+            result = match rng.range(0, 3) {
+                0 => result + number,
+                1 => result * number,
+                _ => format!("{}{}", result, number).parse().unwrap(),
+            };
+        }
+        if i % 2 == 1 {
+            result += 1;
+        }
+        let numbers: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+        let _ = writeln!(input, "{}: {}", result, numbers.join(" "));
+    }
+    input
+}
+
+pub fn antenna_map(rng: &mut Rng, size: usize, antennas: usize) -> String {
+    let frequencies = ['0', 'a', 'A', 'z', 'Z', '9'];
+    let percent = (antennas * 100 / (size * size)).max(1) as u64;
+    grid(size, |_, _| {
+        if rng.chance(percent) {
+            rng.pick(&frequencies)
+        } else {
+            '.'
+        }
+    })
+}
+
+pub fn disk_map(rng: &mut Rng, length: usize) -> String {
+    (0..length)
+        .map(|i| {
+            let low = if i % 2 == 0 { 1 } else { 0 };
+            char::from_digit(rng.range(low, 10) as u32, 10).unwrap()
+        })
+        .collect()
+}
+
+// slopes that rise to the right and downwards, so there are plenty of hiking trails
+pub fn topographic_map(rng: &mut Rng, size: usize) -> String {
+    grid(size, |x, y| {
+        let height = (x + y + rng.range(0, 2) as usize) % 10;
+        char::from_digit(height as u32, 10).unwrap()
+    })
+}
+
+pub fn stones(rng: &mut Rng, count: usize) -> String {
+    let stones: Vec<String> = (0..count)
+        .map(|_| rng.range(0, 1_000_000).to_string())
+        .collect();
+    stones.join(" ") + "\n"
+}
+
+pub fn claw_machines(rng: &mut Rng, count: usize) -> String {
+    let machines: Vec<String> = (0..count)
+        .map(|_| {
+            let (ax, ay) = (rng.range(10, 100), rng.range(10, 100));
+            let (bx, by) = (rng.range(10, 100), rng.range(10, 100));
+            let (a, b) = (rng.range(0, 100), rng.range(0, 100));
+            format!(
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+                ax,
+                ay,
+                bx,
+                by,
+                a * ax + b * bx,
+                a * ay + b * by
+            )
+        })
+        .collect();
+    machines.join("\n")
+}
+
+pub fn robots(rng: &mut Rng, count: usize, width: i64, height: i64) -> String {
+    let mut input = String::new();
+    for _ in 0..count {
+        let _ = writeln!(
+            input,
+            "p={},{} v={},{}",
+            rng.range(0, width),
+            rng.range(0, height),
+            rng.range(-100, 101),
+            rng.range(-100, 101)
+        );
+    }
+    input
+}
+
+pub fn warehouse(rng: &mut Rng, size: usize, movements: usize) -> String {
+    let robot = (size / 2, size / 2);
+    let mut input = grid(size, |x, y| match (x, y) {
+        _ if x == 0 || y == 0 || x == size - 1 || y == size - 1 => '#',
+        _ if (x, y) == robot => '@',
+        _ if rng.chance(3) => '#',
+        _ if rng.chance(25) => 'O',
+        _ => '.',
+    });
+    input.push('\n');
+    for i in 0..movements {
+        input.push(rng.pick(&['^', '>', 'v', '<']));
+        if i % 70 == 69 {
+            input.push('\n');
+        }
+    }
+    input.push('\n');
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_generate_grids() {
+        let lab = lab(&mut Rng::new(1), 5);
+        assert_eq!(5, lab.lines().count());
+        assert!(lab.lines().all(|line| line.len() == 5));
+        assert_eq!('^', lab.lines().nth(2).unwrap().chars().nth(2).unwrap());
+    }
+
+    #[test]
+    fn can_generate_equations() {
+        let equations = equations(&mut Rng::new(1), 2, 4);
+        let numbers: Vec<Vec<i64>> = equations
+            .lines()
+            .map(|line| {
+                line.replace(':', "")
+                    .split(' ')
+                    .map(|n| n.parse().unwrap())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(2, numbers.len());
+        assert!(numbers.iter().all(|equation| equation.len() == 5));
+    }
+}
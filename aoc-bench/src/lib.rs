@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use aoc_core::stats::format_nanos;
+
+pub mod inputs;
+
+const WARM_UP_TIME: Duration = Duration::from_millis(100);
+const SAMPLE_TIME: Duration = Duration::from_millis(20);
+const SAMPLE_COUNT: usize = 20;
+// changes smaller than this are most likely noise
+const NOISE_THRESHOLD: f64 = 0.1;
+
+// a tiny xorshift generator, so every run benchmarks the very same inputs
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // a number in `low..high`
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next_u64() % (high - low) as u64) as i64
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.range(0, items.len() as i64) as usize]
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Measurement {
+    pub name: String,
+    // nanoseconds per iteration
+    pub median: f64,
+    pub mean: f64,
+}
+
+impl Measurement {
+    fn new(name: &str, mut samples: Vec<f64>) -> Measurement {
+        samples.sort_by(|a, b| a.total_cmp(b));
+        let median = samples[samples.len() / 2];
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        Measurement {
+            name: name.to_string(),
+            median,
+            mean,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Change {
+    New,
+    Unchanged(f64),
+    Improved(f64),
+    Regressed(f64),
+}
+
+impl Change {
+    pub fn between(before: Option<f64>, after: f64) -> Change {
+        let Some(before) = before else {
+            return Change::New;
+        };
+        let change = (after - before) / before;
+        if change > NOISE_THRESHOLD {
+            Change::Regressed(change)
+        } else if change < -NOISE_THRESHOLD {
+            Change::Improved(change)
+        } else {
+            Change::Unchanged(change)
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum Mode {
+    // `cargo bench`
+    Measure,
+    // `cargo test --benches`, run everything once to see that it works
+    Check,
+}
+
+// `cargo bench -p aoc-bench [-- [FILTER] [--baseline NAME] [--save-baseline NAME]]`
+//
+// every run is compared with the last one saved under the same baseline name (`latest`
+// unless given) in `target/aoc-bench/`. `--baseline` compares without saving, so a named
+// baseline stays put while you try things.
+pub struct Bencher {
+    mode: Mode,
+    filter: Option<String>,
+    baseline: String,
+    save: bool,
+    measurements: Vec<Measurement>,
+}
+
+impl Bencher {
+    pub fn from_args() -> Bencher {
+        let mut bencher = Bencher {
+            mode: Mode::Check,
+            filter: None,
+            baseline: "latest".to_string(),
+            save: true,
+            measurements: Vec::new(),
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--bench" => bencher.mode = Mode::Measure,
+                "--baseline" => {
+                    bencher.baseline = args.next().expect("--baseline needs a name");
+                    bencher.save = false;
+                }
+                "--save-baseline" => {
+                    bencher.baseline = args.next().expect("--save-baseline needs a name");
+                    bencher.save = true;
+                }
+                arg if arg.starts_with("--") => (),
+                filter => bencher.filter = Some(filter.to_string()),
+            }
+        }
+        bencher
+    }
+
+    // `setup` prepares a fresh input for every iteration and is not measured, so routines
+    // that change their input (e.g. compacting a disk) can be benchmarked as well.
+    pub fn bench<I, O>(
+        &mut self,
+        name: &str,
+        mut setup: impl FnMut() -> I,
+        mut routine: impl FnMut(I) -> O,
+    ) {
+        if let Some(filter) = &self.filter {
+            if !name.contains(filter.as_str()) {
+                return;
+            }
+        }
+        if self.mode == Mode::Check {
+            black_box(routine(setup()));
+            println!("{} ... ok", name);
+            return;
+        }
+
+        let mut run = |iterations: u64| {
+            let inputs: Vec<I> = (0..iterations).map(|_| setup()).collect();
+            let start = Instant::now();
+            for input in inputs {
+                black_box(routine(input));
+            }
+            start.elapsed()
+        };
+
+        let mut warm_up_iterations: u64 = 0;
+        let mut warm_up_elapsed = Duration::ZERO;
+        while warm_up_elapsed < WARM_UP_TIME {
+            warm_up_elapsed += run(1);
+            warm_up_iterations += 1;
+        }
+        let estimate = warm_up_elapsed.as_nanos() as f64 / warm_up_iterations as f64;
+        let iterations = ((SAMPLE_TIME.as_nanos() as f64 / estimate) as u64).max(1);
+
+        let samples: Vec<f64> = (0..SAMPLE_COUNT)
+            .map(|_| run(iterations).as_nanos() as f64 / iterations as f64)
+            .collect();
+        let measurement = Measurement::new(name, samples);
+        println!("{:<40} {:>10}", name, format_nanos(measurement.median));
+        self.measurements.push(measurement);
+    }
+
+    // prints the summary and saves the measurements for the next run to compare with
+    pub fn finish(self) {
+        if self.mode == Mode::Check {
+            return;
+        }
+        let path = results_folder().join(format!("{}.tsv", self.baseline));
+        let before = fs::read_to_string(&path)
+            .map(|content| parse_results(&content))
+            .unwrap_or_default();
+
+        println!();
+        println!(
+            "{:<40} {:>10} {:>10} {:>10}",
+            "benchmark", "median", "mean", "change"
+        );
+        let mut regressions = 0;
+        let mut improvements = 0;
+        for measurement in &self.measurements {
+            let change =
+                Change::between(before.get(&measurement.name).copied(), measurement.median);
+            let change = match change {
+                Change::New => "new".to_string(),
+                Change::Unchanged(change) => format!("{:+.1}%", change * 100.0),
+                Change::Improved(change) => {
+                    improvements += 1;
+                    format!("{:+.1}% faster", change * 100.0)
+                }
+                Change::Regressed(change) => {
+                    regressions += 1;
+                    format!("{:+.1}% SLOWER", change * 100.0)
+                }
+            };
+            println!(
+                "{:<40} {:>10} {:>10} {:>10}",
+                measurement.name,
+                format_nanos(measurement.median),
+                format_nanos(measurement.mean),
+                change
+            );
+        }
+        if before.is_empty() {
+            println!(
+                "\nno earlier run saved as {:?} to compare with",
+                self.baseline
+            );
+        } else {
+            println!(
+                "\ncompared with target/aoc-bench/{}.tsv: {} regressed, {} improved",
+                self.baseline, regressions, improvements
+            );
+        }
+
+        if self.save {
+            let mut results = before;
+            for measurement in &self.measurements {
+                results.insert(measurement.name.clone(), measurement.median);
+            }
+            fs::create_dir_all(results_folder()).expect("Failed to create results folder!");
+            fs::write(&path, format_results(&results)).expect("Failed to save results!");
+        }
+    }
+}
+
+fn results_folder() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("target")
+        .join("aoc-bench")
+}
+
+// one `name<TAB>median in nanoseconds` line per benchmark
+fn parse_results(content: &str) -> HashMap<String, f64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (name, median) = line.split_once('\t')?;
+            Some((name.to_string(), median.parse().ok()?))
+        })
+        .collect()
+}
+
+fn format_results(results: &HashMap<String, f64>) -> String {
+    let mut names: Vec<&String> = results.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| format!("{}\t{}\n", name, results[name]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_repeat_random_numbers() {
+        let mut rng = Rng::new(7);
+        let first: Vec<i64> = (0..5).map(|_| rng.range(10, 20)).collect();
+        let mut rng = Rng::new(7);
+        let second: Vec<i64> = (0..5).map(|_| rng.range(10, 20)).collect();
+        assert_eq!(first, second);
+        assert!(first.iter().all(|n| (10..20).contains(n)));
+    }
+
+    #[test]
+    fn can_summarize_samples() {
+        let measurement = Measurement::new("dec01", vec![3.0, 1.0, 2.0, 10.0]);
+        assert_eq!(3.0, measurement.median);
+        assert_eq!(4.0, measurement.mean);
+    }
+
+    #[test]
+    fn can_compare_runs() {
+        assert_eq!(Change::New, Change::between(None, 100.0));
+        assert_eq!(Change::Unchanged(0.05), Change::between(Some(100.0), 105.0));
+        assert_eq!(Change::Regressed(0.5), Change::between(Some(100.0), 150.0));
+        assert_eq!(Change::Improved(-0.5), Change::between(Some(100.0), 50.0));
+    }
+
+    #[test]
+    fn can_save_and_load_results() {
+        let results = HashMap::from([("dec09/compact".to_string(), 1234.5)]);
+        assert_eq!(results, parse_results(&format_results(&results)));
+    }
+}
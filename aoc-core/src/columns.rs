@@ -0,0 +1,227 @@
+use std::str::FromStr;
+
+use crate::{parse_field, whitespace_fields, ParseError};
+
+// the line number and the cells of a line, with the column every cell starts at
+type Row<'a> = (usize, Vec<(usize, &'a str)>);
+
+// reads whitespace separated numbers, one row per line. blank lines are skipped, so are the
+// first `header` lines and lines that start with the comment prefix. line numbers in errors
+// still count every line of the input.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnReader {
+    columns: Option<usize>,
+    header: usize,
+    comment: Option<String>,
+}
+
+impl ColumnReader {
+    // any number of columns, rows don't have to be the same length
+    pub fn new() -> ColumnReader {
+        ColumnReader::default()
+    }
+
+    pub fn with_columns(mut self, columns: usize) -> ColumnReader {
+        self.columns = Some(columns);
+        self
+    }
+
+    pub fn with_header(mut self, lines: usize) -> ColumnReader {
+        self.header = lines;
+        self
+    }
+
+    pub fn with_comments(mut self, prefix: &str) -> ColumnReader {
+        self.comment = Some(prefix.to_string());
+        self
+    }
+
+    // every row that is not skipped
+    fn cells<'a>(
+        &'a self,
+        input: &'a str,
+    ) -> impl Iterator<Item = Result<Row<'a>, ParseError>> + 'a {
+        input
+            .lines()
+            .enumerate()
+            .skip(self.header)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter(move |(_, line)| match &self.comment {
+                Some(prefix) => !line.trim_start().starts_with(prefix.as_str()),
+                None => true,
+            })
+            .map(move |(i, line)| {
+                let cells: Vec<(usize, &str)> = whitespace_fields(line).collect();
+                match self.columns {
+                    Some(columns) if cells.len() < columns => Err(ParseError::missing(
+                        i + 1,
+                        line.trim_end().chars().count() + 1,
+                        &format!("{} numbers", columns),
+                    )),
+                    Some(columns) if cells.len() > columns => {
+                        let (column, cell) = cells[columns];
+                        Err(ParseError::unexpected(
+                            i + 1,
+                            column,
+                            cell,
+                            "the end of the line",
+                        ))
+                    }
+                    _ => Ok((i + 1, cells)),
+                }
+            })
+    }
+
+    pub fn rows<T: FromStr>(&self, input: &str) -> Result<Vec<Vec<T>>, ParseError> {
+        self.cells(input)
+            .map(|row| {
+                let (line, cells) = row?;
+                cells
+                    .into_iter()
+                    .map(|(column, cell)| parse_field(cell, line, column, "a number"))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // the same as `rows`, but one vector per column. without `with_columns` every row needs
+    // as many columns as the first one.
+    pub fn columns<T: FromStr>(&self, input: &str) -> Result<Vec<Vec<T>>, ParseError> {
+        let count = match self.columns {
+            Some(count) => count,
+            None => match self.cells(input).next() {
+                Some(row) => row?.1.len(),
+                None => 0,
+            },
+        };
+        let reader = self.clone().with_columns(count);
+        let mut columns: Vec<Vec<T>> = (0..count).map(|_| Vec::new()).collect();
+        for row in reader.rows(input)? {
+            for (column, number) in columns.iter_mut().zip(row) {
+                column.push(number);
+            }
+        }
+        Ok(columns)
+    }
+
+    // one `R` per row, the number of columns comes from `R`
+    pub fn read<R: FromRow>(&self, input: &str) -> Result<Vec<R>, ParseError> {
+        let reader = self.clone().with_columns(R::COLUMNS);
+        reader
+            .cells(input)
+            .map(|row| {
+                let (line, cells) = row?;
+                R::from_row(line, &cells)
+            })
+            .collect()
+    }
+}
+
+// a type that can be built from one row of `ColumnReader`. `cells` holds exactly `COLUMNS`
+// cells and the column each of them starts at.
+pub trait FromRow: Sized {
+    const COLUMNS: usize;
+
+    fn from_row(line: usize, cells: &[(usize, &str)]) -> Result<Self, ParseError>;
+}
+
+pub fn parse_cell<T: FromStr>(line: usize, cell: (usize, &str)) -> Result<T, ParseError> {
+    parse_field(cell.1, line, cell.0, "a number")
+}
+
+impl<A: FromStr, B: FromStr> FromRow for (A, B) {
+    const COLUMNS: usize = 2;
+
+    fn from_row(line: usize, cells: &[(usize, &str)]) -> Result<(A, B), ParseError> {
+        Ok((parse_cell(line, cells[0])?, parse_cell(line, cells[1])?))
+    }
+}
+
+impl<A: FromStr, B: FromStr, C: FromStr> FromRow for (A, B, C) {
+    const COLUMNS: usize = 3;
+
+    fn from_row(line: usize, cells: &[(usize, &str)]) -> Result<(A, B, C), ParseError> {
+        Ok((
+            parse_cell(line, cells[0])?,
+            parse_cell(line, cells[1])?,
+            parse_cell(line, cells[2])?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_read_rows() {
+        let input = "left right\n# a comment\n3   4\n\n  # another one\n4 3 7\n";
+        let reader = ColumnReader::new().with_header(1).with_comments("#");
+        assert_eq!(
+            Ok(vec![vec![3, 4], vec![4, 3, 7]]),
+            reader.rows::<u8>(input)
+        );
+    }
+
+    #[test]
+    fn can_read_columns() {
+        let reader = ColumnReader::new().with_columns(2);
+        assert_eq!(
+            Ok(vec![vec![3, 4, 2], vec![4, 3, 5]]),
+            reader.columns::<i64>("3   4\n4   3\n2   5\n")
+        );
+
+        // the first row decides how many columns there are
+        let reader = ColumnReader::new().with_header(1);
+        assert_eq!(
+            Ok(vec![vec![3, 4], vec![4, 3]]),
+            reader.columns::<i64>("left right\n3   4\n4   3\n")
+        );
+        assert_eq!(
+            Err(ParseError::missing(3, 2, "2 numbers")),
+            reader.columns::<i64>("left right\n3   4\n4\n")
+        );
+        assert_eq!(Ok(vec![]), reader.columns::<i64>("left right\n"));
+    }
+
+    #[test]
+    fn can_read_tuples() {
+        struct Pair(i32, u64);
+
+        impl FromRow for Pair {
+            const COLUMNS: usize = 2;
+
+            fn from_row(line: usize, cells: &[(usize, &str)]) -> Result<Pair, ParseError> {
+                let (a, b) = <(i32, u64)>::from_row(line, cells)?;
+                Ok(Pair(a, b))
+            }
+        }
+
+        let reader = ColumnReader::new();
+        let triples: Vec<(i8, i16, i32)> = reader.read("1 2 3\n-4 5 6\n").unwrap();
+        assert_eq!(vec![(1, 2, 3), (-4, 5, 6)], triples);
+        let pairs: Vec<Pair> = reader.read("-1 2\n").unwrap();
+        assert_eq!((-1, 2), (pairs[0].0, pairs[0].1));
+    }
+
+    #[test]
+    fn can_report_cell_locations() {
+        let reader = ColumnReader::new().with_columns(2).with_comments("#");
+        assert_eq!(
+            Err(ParseError::unexpected(3, 5, "x", "a number")),
+            reader.rows::<i32>("1 2\n# 1\n3   x\n")
+        );
+        assert_eq!(
+            Err(ParseError::missing(2, 2, "2 numbers")),
+            reader.rows::<i32>("1 2\n3\n")
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(1, 5, "3", "the end of the line")),
+            reader.rows::<i32>("1 2 3\n")
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(1, 3, "-2", "a number")),
+            reader.read::<(u32, u32)>("1 -2\n")
+        );
+    }
+}
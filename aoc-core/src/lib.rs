@@ -0,0 +1,246 @@
+use std::fmt;
+use std::process;
+use std::str::FromStr;
+
+pub mod columns;
+pub mod stats;
+
+// lines and columns start at 1, columns count characters rather than bytes.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ParseError {
+    // something is there, but it's not what we were looking for
+    Unexpected {
+        line: usize,
+        column: usize,
+        text: String,
+        expected: String,
+    },
+    // the input ended before we found what we were looking for
+    Missing {
+        line: usize,
+        column: usize,
+        expected: String,
+    },
+}
+
+impl ParseError {
+    pub fn unexpected(line: usize, column: usize, text: &str, expected: &str) -> ParseError {
+        ParseError::Unexpected {
+            line,
+            column,
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn missing(line: usize, column: usize, expected: &str) -> ParseError {
+        ParseError::Missing {
+            line,
+            column,
+            expected: expected.to_string(),
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        match self {
+            ParseError::Unexpected { line, .. } | ParseError::Missing { line, .. } => *line,
+        }
+    }
+
+    pub fn column(&self) -> usize {
+        match self {
+            ParseError::Unexpected { column, .. } | ParseError::Missing { column, .. } => *column,
+        }
+    }
+
+    // moves the location of an error that was found in a snippet to where the snippet starts
+    // in the whole input, e.g. `shift(4, 0)` if the snippet was the fifth line.
+    pub fn shift(self, lines: usize, columns: usize) -> ParseError {
+        match self {
+            ParseError::Unexpected {
+                line,
+                column,
+                text,
+                expected,
+            } => ParseError::Unexpected {
+                line: line + lines,
+                column: column + columns,
+                text,
+                expected,
+            },
+            ParseError::Missing {
+                line,
+                column,
+                expected,
+            } => ParseError::Missing {
+                line: line + lines,
+                column: column + columns,
+                expected,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Unexpected {
+                line,
+                column,
+                text,
+                expected,
+            } => write!(
+                f,
+                "line {}, column {}: expected {}, found {:?}",
+                line, column, expected, text
+            ),
+            ParseError::Missing {
+                line,
+                column,
+                expected,
+            } => write!(
+                f,
+                "line {}, column {}: expected {}, found nothing",
+                line, column, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// one day's puzzle. parsing lives in the library so it can be tested, both parts work on
+// whatever `parse` came up with.
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> String;
+
+    // `None` if the part has not been solved yet
+    fn part2(input: &Self::Input) -> Option<String>;
+}
+
+pub trait OrExit<T> {
+    // prints the error prefixed with the name of the input and ends the process
+    fn or_exit(self, source: &str) -> T;
+}
+
+impl<T> OrExit<T> for Result<T, ParseError> {
+    fn or_exit(self, source: &str) -> T {
+        self.unwrap_or_else(|error| {
+            eprintln!("{}: {}", source, error);
+            process::exit(1);
+        })
+    }
+}
+
+pub fn column_at(line: &str, byte_index: usize) -> usize {
+    line[..byte_index].chars().count() + 1
+}
+
+// splits on the separator and remembers the column every field starts at
+pub fn fields<'a>(line: &'a str, separator: &'a str) -> impl Iterator<Item = (usize, &'a str)> {
+    line.split(separator).scan(1, move |column, field| {
+        let start = *column;
+        *column += field.chars().count() + separator.chars().count();
+        Some((start, field))
+    })
+}
+
+pub fn whitespace_fields(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace().map(move |field| {
+        (
+            column_at(line, field.as_ptr() as usize - line.as_ptr() as usize),
+            field,
+        )
+    })
+}
+
+// splits on blank lines and remembers how many lines come before every block
+pub fn blocks(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.split("\n\n").scan(0, |offset, block| {
+        let start = *offset;
+        *offset += block.lines().count() + 1;
+        Some((start, block))
+    })
+}
+
+pub fn parse_field<T: FromStr>(
+    text: &str,
+    line: usize,
+    column: usize,
+    expected: &str,
+) -> Result<T, ParseError> {
+    text.parse()
+        .map_err(|_| ParseError::unexpected(line, column, text, expected))
+}
+
+// at least one number, a blank line is missing its numbers
+pub fn parse_numbers<T: FromStr>(line: &str, line_number: usize) -> Result<Vec<T>, ParseError> {
+    let numbers: Vec<T> = whitespace_fields(line)
+        .map(|(column, field)| parse_field(field, line_number, column, "a number"))
+        .collect::<Result<_, _>>()?;
+    if numbers.is_empty() {
+        return Err(ParseError::missing(
+            line_number,
+            column_at(line, line.len()),
+            "a number",
+        ));
+    }
+    Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_display_errors() {
+        let error = ParseError::unexpected(3, 5, "x", "a digit");
+        assert_eq!(
+            "line 3, column 5: expected a digit, found \"x\"",
+            error.to_string()
+        );
+        let error = ParseError::missing(2, 1, "a robot");
+        assert_eq!(
+            "line 2, column 1: expected a robot, found nothing",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn can_shift_errors() {
+        let error = ParseError::unexpected(1, 5, "x", "a digit").shift(4, 2);
+        assert_eq!(5, error.line());
+        assert_eq!(7, error.column());
+    }
+
+    #[test]
+    fn can_split_fields() {
+        let fields: Vec<(usize, &str)> = fields("75,47,161", ",").collect();
+        assert_eq!(vec![(1, "75"), (4, "47"), (7, "161")], fields);
+        let fields: Vec<(usize, &str)> = whitespace_fields("3   4  x").collect();
+        assert_eq!(vec![(1, "3"), (5, "4"), (8, "x")], fields);
+    }
+
+    #[test]
+    fn can_split_blocks() {
+        let blocks: Vec<(usize, &str)> = blocks("a\nb\n\nc\n\nd\ne\n").collect();
+        assert_eq!(vec![(0, "a\nb"), (3, "c"), (5, "d\ne\n")], blocks);
+    }
+
+    #[test]
+    fn can_parse_numbers() {
+        assert_eq!(Ok(vec![7, 6, 4]), parse_numbers::<i32>("7 6 4", 1));
+        assert_eq!(
+            Err(ParseError::unexpected(2, 5, "4x", "a number")),
+            parse_numbers::<i32>(" 7  4x", 2)
+        );
+        assert_eq!(
+            Err(ParseError::missing(3, 3, "a number")),
+            parse_numbers::<i32>("  ", 3)
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+// the system allocator, but it keeps track of how many bytes are in use. every day binary
+// installs it with
+//
+//     #[global_allocator]
+//     static ALLOCATOR: CountingAllocator = CountingAllocator;
+pub struct CountingAllocator;
+
+fn allocated(size: usize) {
+    let current = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+fn freed(size: usize) {
+    ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            freed(layout.size());
+            allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Debug)]
+pub struct Phase {
+    pub name: String,
+    pub elapsed: Duration,
+    // the most memory the phase had allocated at once, on top of what was there before.
+    // stays 0 unless the binary uses the `CountingAllocator`.
+    pub peak_bytes: usize,
+}
+
+pub struct Stats {
+    format: Option<Format>,
+    phases: Vec<Phase>,
+}
+
+impl Stats {
+    pub fn new(format: Option<Format>) -> Stats {
+        Stats {
+            format,
+            phases: Vec::new(),
+        }
+    }
+
+    // `--stats` prints a table, `--stats=json` prints json, both to stderr
+    pub fn from_args() -> Stats {
+        let format = env::args().skip(1).find_map(|arg| match arg.as_str() {
+            "--stats" => Some(Format::Table),
+            "--stats=json" => Some(Format::Json),
+            _ => None,
+        });
+        Stats::new(format)
+    }
+
+    pub fn measure<T>(&mut self, name: &str, phase: impl FnOnce() -> T) -> T {
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(before, Ordering::Relaxed);
+        let start = Instant::now();
+        let result = phase();
+        let elapsed = start.elapsed();
+        let peak_bytes = PEAK.load(Ordering::Relaxed).saturating_sub(before);
+
+        self.phases.push(Phase {
+            name: name.to_string(),
+            elapsed,
+            peak_bytes,
+        });
+        result
+    }
+
+    pub fn phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    pub fn table(&self) -> String {
+        let mut table = format!("{:<20} {:>10} {:>12}\n", "phase", "time", "peak memory");
+        for phase in &self.phases {
+            table += &format!(
+                "{:<20} {:>10} {:>12}\n",
+                phase.name,
+                format_nanos(phase.elapsed.as_nanos() as f64),
+                format_bytes(phase.peak_bytes)
+            );
+        }
+        let total: Duration = self.phases.iter().map(|phase| phase.elapsed).sum();
+        let peak = self.phases.iter().map(|phase| phase.peak_bytes).max();
+        table += &format!(
+            "{:<20} {:>10} {:>12}\n",
+            "total",
+            format_nanos(total.as_nanos() as f64),
+            format_bytes(peak.unwrap_or(0))
+        );
+        table
+    }
+
+    pub fn json(&self) -> String {
+        let phases: Vec<String> = self
+            .phases
+            .iter()
+            .map(|phase| {
+                format!(
+                    "{{\"phase\":{:?},\"nanos\":{},\"peak_bytes\":{}}}",
+                    phase.name,
+                    phase.elapsed.as_nanos(),
+                    phase.peak_bytes
+                )
+            })
+            .collect();
+        format!("[{}]", phases.join(","))
+    }
+
+    // does nothing unless the stats were asked for
+    pub fn report(&self) {
+        match self.format {
+            Some(Format::Table) => eprint!("{}", self.table()),
+            Some(Format::Json) => eprintln!("{}", self.json()),
+            None => (),
+        }
+    }
+}
+
+pub fn format_nanos(nanos: f64) -> String {
+    if nanos < 1_000.0 {
+        format!("{:.1}ns", nanos)
+    } else if nanos < 1_000_000.0 {
+        format!("{:.2}µs", nanos / 1_000.0)
+    } else if nanos < 1_000_000_000.0 {
+        format!("{:.2}ms", nanos / 1_000_000.0)
+    } else {
+        format!("{:.2}s", nanos / 1_000_000_000.0)
+    }
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn can_measure_phases() {
+        let mut stats = Stats::new(Some(Format::Json));
+        let sum: usize = stats.measure("part 1", || vec![1; 100_000].iter().sum());
+        assert_eq!(100_000, sum);
+        assert_eq!(1, stats.phases().len());
+        assert_eq!("part 1", stats.phases()[0].name);
+        assert!(stats.phases()[0].peak_bytes >= 100_000 * size_of::<usize>());
+    }
+
+    #[test]
+    fn can_print_json() {
+        let mut stats = Stats::new(Some(Format::Json));
+        stats.phases.push(Phase {
+            name: "parse".to_string(),
+            elapsed: Duration::from_nanos(1500),
+            peak_bytes: 2048,
+        });
+        assert_eq!(
+            "[{\"phase\":\"parse\",\"nanos\":1500,\"peak_bytes\":2048}]",
+            stats.json()
+        );
+    }
+
+    #[test]
+    fn can_format_units() {
+        assert_eq!("12.0ns", format_nanos(12.0));
+        assert_eq!("1.50µs", format_nanos(1_500.0));
+        assert_eq!("2.25ms", format_nanos(2_250_000.0));
+        assert_eq!("3.00s", format_nanos(3_000_000_000.0));
+        assert_eq!("512 B", format_bytes(512));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+        assert_eq!("2.0 MiB", format_bytes(2 * 1024 * 1024));
+    }
+}
@@ -0,0 +1,519 @@
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Sub};
+use std::str::FromStr;
+
+use aoc_core::ParseError;
+
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    pub fn step(self, direction: Direction) -> Point {
+        let (x_delta, y_delta) = direction.delta();
+        Point {
+            x: self.x + x_delta,
+            y: self.y + y_delta,
+        }
+    }
+
+    pub fn steps(self, direction: Direction, count: i32) -> Point {
+        let (x_delta, y_delta) = direction.delta();
+        Point {
+            x: self.x + x_delta * count,
+            y: self.y + y_delta * count,
+        }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+// y grows downwards, just like the lines of the puzzle input.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::UpRight => (1, -1),
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (0, 1),
+            Direction::DownLeft => (-1, 1),
+            Direction::Left => (-1, 0),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.rotate(6)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    // rotates clockwise in steps of 45 degrees
+    fn rotate(self, eighths: usize) -> Direction {
+        let index = Direction::ALL.iter().position(|&d| d == self).unwrap();
+        Direction::ALL[(index + eighths) % 8]
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, ParseError> {
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        if width == 0 {
+            return Err(ParseError::missing(1, 1, "a grid"));
+        }
+        let height = rows.len();
+        let mut cells: Vec<T> = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(ParseError::unexpected(
+                    y + 1,
+                    1,
+                    &format!("{} cells", row.len()),
+                    &format!("{} cells", width),
+                ));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    pub fn parse<F>(input: &str, convert: F) -> Result<Grid<T>, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        Grid::parse_lines(input.lines(), convert)
+    }
+
+    pub fn parse_lines<'a, I, F>(lines: I, mut convert: F) -> Result<Grid<T>, ParseError>
+    where
+        I: IntoIterator<Item = &'a str>,
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for (y, line) in lines.into_iter().enumerate() {
+            let mut row: Vec<T> = Vec::new();
+            for (x, ch) in line.chars().enumerate() {
+                let cell = convert(ch).ok_or_else(|| {
+                    ParseError::unexpected(y + 1, x + 1, &ch.to_string(), "a valid cell")
+                })?;
+                row.push(cell);
+            }
+            if let Some(first) = rows.first() {
+                if first.len() != row.len() {
+                    let expected = format!("a row of {} cells", first.len());
+                    return Err(ParseError::unexpected(y + 1, 1, line, &expected));
+                }
+            }
+            rows.push(row);
+        }
+        Grid::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    pub fn set(&mut self, point: Point, value: T) {
+        self[point] = value;
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.width * self.height)
+            .map(move |i| Point::new((i % width) as i32, (i / width) as i32))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn position<P>(&self, mut predicate: P) -> Option<Point>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter().find(|(_, t)| predicate(t)).map(|(p, _)| p)
+    }
+
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbours(point, &Direction::CARDINAL)
+    }
+
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbours(point, &Direction::ALL)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        directions: &'static [Direction],
+    ) -> impl Iterator<Item = (Point, &'a T)> {
+        directions.iter().filter_map(move |&direction| {
+            let neighbour = point.step(direction);
+            self.get(neighbour).map(|t| (neighbour, t))
+        })
+    }
+
+    // all cells from `start` (inclusive) in `direction` until we fall off the grid
+    pub fn ray(&self, start: Point, direction: Direction) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            current: start,
+            direction,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> Ray<'_, T> {
+        self.ray(Point::new(x as i32, 0), Direction::Down)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = Ray<'_, T>> {
+        self.lines(Direction::Down)
+    }
+
+    // top-left to bottom-right
+    pub fn diagonals(&self) -> impl Iterator<Item = Ray<'_, T>> {
+        self.lines(Direction::DownRight)
+    }
+
+    // bottom-left to top-right
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = Ray<'_, T>> {
+        self.lines(Direction::UpRight)
+    }
+
+    // every maximal line running in `direction`, e.g. all rows for `Right`
+    pub fn lines(&self, direction: Direction) -> impl Iterator<Item = Ray<'_, T>> {
+        let back = direction.opposite();
+        self.points()
+            .filter(move |&p| !self.contains(p.step(back)))
+            .map(move |p| self.ray(p, direction))
+    }
+
+    // wraps a point around the edges, as if the grid was a torus
+    pub fn wrap(&self, point: Point) -> Point {
+        Point {
+            x: point.x.rem_euclid(self.width as i32),
+            y: point.y.rem_euclid(self.height as i32),
+        }
+    }
+
+    pub fn swap(&mut self, a: Point, b: Point) {
+        let a = self.checked_index_of(a);
+        let b = self.checked_index_of(b);
+        self.cells.swap(a, b);
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        if point.x < 0 || point.y < 0 {
+            return None;
+        }
+        let (x, y) = (point.x as usize, point.y as usize);
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(y * self.width + x)
+    }
+
+    fn checked_index_of(&self, point: Point) -> usize {
+        self.index_of(point).unwrap_or_else(|| {
+            panic!(
+                "{} is outside of the {}x{} grid",
+                point, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        &self.cells[self.checked_index_of(point)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let i = self.checked_index_of(point);
+        &mut self.cells[i]
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse(input, Some)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    current: Point,
+    direction: Direction,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = (Point, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.current;
+        let cell = self.grid.get(point)?;
+        self.current = point.step(self.direction);
+        Some((point, cell))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn letters() -> Grid<char> {
+        "abc\ndef\nghi".parse().unwrap()
+    }
+
+    fn collect(ray: Ray<'_, char>) -> String {
+        ray.map(|(_, &ch)| ch).collect()
+    }
+
+    #[test]
+    fn can_parse_grid() {
+        let grid = letters();
+        assert_eq!(3, grid.width());
+        assert_eq!(3, grid.height());
+        assert_eq!('a', grid[Point::new(0, 0)]);
+        assert_eq!('f', grid[Point::new(2, 1)]);
+        assert_eq!(Some(&'h'), grid.get(Point::new(1, 2)));
+    }
+
+    #[test]
+    fn can_reject_bad_grids() {
+        assert_eq!(
+            Err(ParseError::missing(1, 1, "a grid")),
+            "".parse::<Grid<char>>()
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(2, 1, "de", "a row of 3 cells")),
+            "abc\nde".parse::<Grid<char>>()
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(1, 2, "x", "a valid cell")),
+            Grid::parse("1x2", |ch| ch.to_digit(10))
+        );
+    }
+
+    #[test]
+    fn can_check_bounds() {
+        let grid = letters();
+        assert!(grid.contains(Point::new(2, 2)));
+        assert!(!grid.contains(Point::new(3, 0)));
+        assert!(!grid.contains(Point::new(0, 3)));
+        assert!(!grid.contains(Point::new(-1, 0)));
+        assert_eq!(None, grid.get(Point::new(0, -1)));
+    }
+
+    #[test]
+    fn can_iterate_neighbours() {
+        let grid = letters();
+        let corner: Vec<char> = grid
+            .neighbours4(Point::new(0, 0))
+            .map(|(_, &c)| c)
+            .collect();
+        assert_eq!(vec!['b', 'd'], corner);
+        let center: Vec<char> = grid
+            .neighbours4(Point::new(1, 1))
+            .map(|(_, &c)| c)
+            .collect();
+        assert_eq!(vec!['b', 'f', 'h', 'd'], center);
+        assert_eq!(3, grid.neighbours8(Point::new(2, 2)).count());
+        assert_eq!(8, grid.neighbours8(Point::new(1, 1)).count());
+    }
+
+    #[test]
+    fn can_turn_directions() {
+        assert_eq!(Direction::Right, Direction::Up.turn_right());
+        assert_eq!(Direction::Up, Direction::Left.turn_right());
+        assert_eq!(Direction::Left, Direction::Up.turn_left());
+        assert_eq!(Direction::UpLeft, Direction::DownRight.opposite());
+        assert_eq!(Point::new(2, 0), Point::new(1, 1).step(Direction::UpRight));
+        assert_eq!(
+            Point::new(-2, 1),
+            Point::new(1, 1).steps(Direction::Left, 3)
+        );
+    }
+
+    #[test]
+    fn can_view_rows_and_columns() {
+        let grid = letters();
+        assert_eq!(&['d', 'e', 'f'], grid.row(1));
+        assert_eq!(3, grid.rows().count());
+        assert_eq!("cfi", collect(grid.column(2)));
+        let columns: Vec<String> = grid.columns().map(collect).collect();
+        assert_eq!(vec!["adg", "beh", "cfi"], columns);
+    }
+
+    #[test]
+    fn can_view_diagonals() {
+        let grid = letters();
+        let diagonals: Vec<String> = grid.diagonals().map(collect).collect();
+        assert_eq!(vec!["aei", "bf", "c", "dh", "g"], diagonals);
+        let anti_diagonals: Vec<String> = grid.anti_diagonals().map(collect).collect();
+        assert_eq!(vec!["a", "db", "gec", "hf", "i"], anti_diagonals);
+        assert_eq!("fed", collect(grid.ray(Point::new(2, 1), Direction::Left)));
+    }
+
+    #[test]
+    fn can_wrap_points() {
+        let grid: Grid<u8> = Grid::new(11, 7, 0);
+        assert_eq!(Point::new(10, 6), grid.wrap(Point::new(-1, -1)));
+        assert_eq!(Point::new(1, 3), grid.wrap(Point::new(12, 10)));
+    }
+
+    #[test]
+    fn can_display_grid() {
+        let mut grid = letters();
+        grid.swap(Point::new(0, 0), Point::new(2, 2));
+        grid.set(Point::new(1, 1), '*');
+        assert_eq!("ibc\nd*f\ngha", grid.to_string());
+        assert_eq!(Some(Point::new(1, 1)), grid.position(|&c| c == '*'));
+    }
+}
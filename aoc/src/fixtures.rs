@@ -0,0 +1,211 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use aoc_core::{parse_field, ParseError};
+
+use crate::scaffold::parse_new_day;
+use crate::{parse_day, run, workspace};
+
+// a puzzle input in `decNN/fixtures/<name>.txt` and the answers we know for it in
+// `decNN/fixtures/<name>.expected`, one `part 1: 42` line per part.
+#[derive(PartialEq, Debug)]
+pub struct Fixture {
+    pub day: u32,
+    pub name: String,
+    pub input: PathBuf,
+    pub expected: Vec<(u32, String)>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Status {
+    Passed,
+    // one line per part that did not match
+    Failed(Vec<String>),
+}
+
+#[derive(Debug)]
+pub struct Outcome {
+    pub day: u32,
+    pub name: String,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+impl Outcome {
+    pub fn passed(&self) -> bool {
+        self.status == Status::Passed
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = format!("dec{:02}/{}", self.day, self.name);
+        let elapsed = self.elapsed.as_secs_f64() * 1000.0;
+        match &self.status {
+            Status::Passed => write!(f, "{:<24} ok      {:>10.2}ms", label, elapsed),
+            Status::Failed(mismatches) => {
+                write!(f, "{:<24} FAILED  {:>10.2}ms", label, elapsed)?;
+                for mismatch in mismatches {
+                    write!(f, "\n    {}", mismatch)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn parse_expected(input: &str) -> Result<Vec<(u32, String)>, ParseError> {
+    let mut expected: Vec<(u32, String)> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (part, answer) = line
+            .strip_prefix("part ")
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or(ParseError::unexpected(
+                i + 1,
+                1,
+                line,
+                "a line like part 1: 42",
+            ))?;
+        let part: u32 = parse_field(part, i + 1, 6, "1 or 2")?;
+        if part != 1 && part != 2 {
+            return Err(ParseError::unexpected(
+                i + 1,
+                6,
+                &part.to_string(),
+                "1 or 2",
+            ));
+        }
+        expected.push((part, answer.trim().to_string()));
+    }
+    Ok(expected)
+}
+
+// collects the fixtures of every workspace member that is a day we can run
+pub fn discover(root: &Path) -> Result<Vec<Fixture>, String> {
+    let mut fixtures: Vec<Fixture> = Vec::new();
+    for member in workspace::members(root)? {
+        let Ok(day) = parse_day(&member) else {
+            continue;
+        };
+        let folder = root.join(&member).join("fixtures");
+        if !folder.is_dir() {
+            continue;
+        }
+
+        let mut inputs: Vec<PathBuf> = fs::read_dir(&folder)
+            .map_err(|e| format!("Failed to read {}: {}", folder.display(), e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .collect();
+        inputs.sort();
+
+        for input in inputs {
+            let expected_path = input.with_extension("expected");
+            let expected = fs::read_to_string(&expected_path)
+                .map_err(|e| format!("Failed to read {}: {}", expected_path.display(), e))?;
+            let expected = parse_expected(&expected)
+                .map_err(|e| format!("{}: {}", expected_path.display(), e))?;
+            let name = input.file_stem().unwrap().to_string_lossy().to_string();
+            fixtures.push(Fixture {
+                day,
+                name,
+                input,
+                expected,
+            });
+        }
+    }
+    Ok(fixtures)
+}
+
+// days with fixtures that `discover` skips because they're not in `DAYS` yet, e.g. right
+// after `cargo aoc new`
+pub fn unregistered(root: &Path) -> Result<Vec<String>, String> {
+    Ok(workspace::members(root)?
+        .into_iter()
+        .filter(|member| parse_new_day(member).is_ok() && parse_day(member).is_err())
+        .filter(|member| root.join(member).join("fixtures").is_dir())
+        .collect())
+}
+
+pub fn check(fixture: &Fixture) -> Outcome {
+    let parts: Vec<u32> = fixture.expected.iter().map(|&(part, _)| part).collect();
+    let start = Instant::now();
+    let answers = fs::read_to_string(&fixture.input)
+        .map_err(|e| format!("Failed to read {}: {}", fixture.input.display(), e))
+        .and_then(|input| run(fixture.day, &parts, &input).map_err(|e| e.to_string()));
+    let elapsed = start.elapsed();
+
+    let status = match answers {
+        Ok(answers) => {
+            let mismatches: Vec<String> = fixture
+                .expected
+                .iter()
+                .zip(answers)
+                .filter(|((_, expected), answer)| answer.value.as_ref() != Some(expected))
+                .map(|((part, expected), answer)| match answer.value {
+                    Some(actual) => format!("part {}: expected {}, got {}", part, expected, actual),
+                    None => format!("part {}: expected {}, not solved yet", part, expected),
+                })
+                .collect();
+            if mismatches.is_empty() {
+                Status::Passed
+            } else {
+                Status::Failed(mismatches)
+            }
+        }
+        Err(error) => Status::Failed(vec![error]),
+    };
+
+    Outcome {
+        day: fixture.day,
+        name: fixture.name.clone(),
+        status,
+        elapsed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_expected() {
+        assert_eq!(
+            Ok(vec![(1, "11".to_string()), (2, "31".to_string())]),
+            parse_expected("part 1: 11\npart 2: 31\n")
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(
+                2,
+                1,
+                "part 2 = 31",
+                "a line like part 1: 42"
+            )),
+            parse_expected("part 1: 11\npart 2 = 31\n")
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(1, 6, "3", "1 or 2")),
+            parse_expected("part 3: 11\n")
+        );
+    }
+
+    #[test]
+    fn can_report_mismatches() {
+        let fixture = Fixture {
+            day: 9,
+            name: "example".to_string(),
+            input: workspace::root().join("dec09/fixtures/example.txt"),
+            expected: vec![(1, "1928".to_string()), (2, "2857".to_string())],
+        };
+        let outcome = check(&fixture);
+        assert_eq!(
+            Status::Failed(vec!["part 2: expected 2857, got 2858".to_string()]),
+            outcome.status
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::workspace;
+
+pub const YEAR: u32 = 2024;
+const DEFAULT_URL: &str = "https://adventofcode.com";
+pub(crate) const USER_AGENT: &str = "github.com/srecnig/aoc24";
+// the site asks to not hammer it, inputs never change once they are downloaded anyway
+const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(PartialEq, Debug)]
+pub enum InputError {
+    // not in the cache and there's no session to download it with
+    NotCached(PathBuf),
+    Http(u16, String),
+    Request(String),
+    Io(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NotCached(path) => write!(
+                f,
+                "{} does not exist, set AOC_SESSION or put your session cookie in .aoc-session to download it",
+                path.display()
+            ),
+            InputError::Http(404, _) => write!(f, "the puzzle input is not available (yet)"),
+            InputError::Http(status, message) => {
+                write!(f, "downloading the input failed with {}: {}", status, message)
+            }
+            InputError::Request(message) => write!(f, "downloading the input failed: {}", message),
+            InputError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+pub trait Backend {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, InputError>;
+}
+
+pub struct HttpBackend {
+    pub(crate) base_url: String,
+    pub(crate) session: String,
+    pub(crate) agent: ureq::Agent,
+}
+
+impl HttpBackend {
+    pub fn new(base_url: &str, session: &str) -> HttpBackend {
+        HttpBackend {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+}
+
+impl HttpBackend {
+    // talks to `AOC_URL` (or the real site) with the session in `AOC_SESSION` or `.aoc-session`
+    pub fn from_env() -> Option<HttpBackend> {
+        let session = env::var("AOC_SESSION").ok().or_else(|| {
+            fs::read_to_string(workspace::root().join(".aoc-session"))
+                .ok()
+                .map(|session| session.trim().to_string())
+        });
+        let session = session.filter(|session| !session.is_empty())?;
+        let url = env::var("AOC_URL").unwrap_or(DEFAULT_URL.to_string());
+        Some(HttpBackend::new(&url, &session))
+    }
+}
+
+impl Backend for HttpBackend {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, InputError> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call();
+        match response {
+            Ok(response) => response
+                .into_string()
+                .map_err(|e| InputError::Request(e.to_string())),
+            Err(ureq::Error::Status(status, response)) => {
+                let message = response.into_string().unwrap_or_default();
+                Err(InputError::Http(status, message.trim().to_string()))
+            }
+            Err(error) => Err(InputError::Request(error.to_string())),
+        }
+    }
+}
+
+// looks for `<root>/<year>/<day>.txt` and downloads whatever is missing. the time of the
+// last download is kept in `<root>/.last-request`, so the interval also holds across runs.
+pub struct InputProvider {
+    root: PathBuf,
+    backend: Option<Box<dyn Backend>>,
+    min_interval: Duration,
+}
+
+impl InputProvider {
+    pub fn new(root: impl Into<PathBuf>) -> InputProvider {
+        InputProvider {
+            root: root.into(),
+            backend: None,
+            min_interval: DEFAULT_MIN_INTERVAL,
+        }
+    }
+
+    // `inputs/` in the workspace, downloads with `HttpBackend::from_env`
+    pub fn from_env() -> InputProvider {
+        let provider = InputProvider::new(workspace::root().join("inputs"));
+        match HttpBackend::from_env() {
+            Some(backend) => provider.with_backend(backend),
+            None => provider,
+        }
+    }
+
+    pub fn with_backend(mut self, backend: impl Backend + 'static) -> InputProvider {
+        self.backend = Some(Box::new(backend));
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> InputProvider {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.root
+            .join(year.to_string())
+            .join(format!("{:02}.txt", day))
+    }
+
+    pub fn get(&self, year: u32, day: u32) -> Result<String, InputError> {
+        let path = self.path(year, day);
+        if let Ok(input) = fs::read_to_string(&path) {
+            return Ok(input);
+        }
+        let Some(backend) = &self.backend else {
+            return Err(InputError::NotCached(path));
+        };
+
+        self.wait_for_turn()?;
+        let input = backend.fetch(year, day)?;
+        let folder = path.parent().unwrap();
+        fs::create_dir_all(folder)
+            .map_err(|e| InputError::Io(format!("Failed to create {}: {}", folder.display(), e)))?;
+        fs::write(&path, &input)
+            .map_err(|e| InputError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+        Ok(input)
+    }
+
+    fn wait_for_turn(&self) -> Result<(), InputError> {
+        let stamp = self.root.join(".last-request");
+        let last_request = fs::read_to_string(&stamp)
+            .ok()
+            .and_then(|nanos| nanos.trim().parse().ok())
+            .map(|nanos| UNIX_EPOCH + Duration::from_nanos(nanos));
+        if let Some(last_request) = last_request {
+            let elapsed = SystemTime::now()
+                .duration_since(last_request)
+                .unwrap_or_default();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        fs::create_dir_all(&self.root).map_err(|e| {
+            InputError::Io(format!("Failed to create {}: {}", self.root.display(), e))
+        })?;
+        fs::write(&stamp, now.as_nanos().to_string())
+            .map_err(|e| InputError::Io(format!("Failed to write {}: {}", stamp.display(), e)))
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+
+use aoc_core::{ParseError, Solution};
+
+pub mod fixtures;
+pub mod inputs;
+pub mod scaffold;
+pub mod submit;
+pub mod workspace;
+
+pub const DAYS: [u32; 14] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 14, 15];
+
+#[derive(PartialEq, Debug)]
+pub struct Answer {
+    pub day: u32,
+    pub part: u32,
+    pub value: Option<String>,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.value.as_deref().unwrap_or("not solved yet");
+        write!(f, "dec{:02} part {}: {}", self.day, self.part, value)
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum RunError {
+    UnknownDay(String),
+    UnknownPart(String),
+    Parse(ParseError),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::UnknownDay(day) => write!(f, "there is no solution for day {:?}", day),
+            RunError::UnknownPart(part) => write!(f, "part must be 1 or 2, not {:?}", part),
+            RunError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+// accepts `1`, `01` and `dec01`
+pub fn parse_day(day: &str) -> Result<u32, RunError> {
+    let number = day.strip_prefix("dec").unwrap_or(day);
+    match number.parse::<u32>() {
+        Ok(n) if DAYS.contains(&n) => Ok(n),
+        _ => Err(RunError::UnknownDay(day.to_string())),
+    }
+}
+
+pub fn parse_part(part: &str) -> Result<u32, RunError> {
+    match part {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(RunError::UnknownPart(part.to_string())),
+    }
+}
+
+pub fn run(day: u32, parts: &[u32], input: &str) -> Result<Vec<Answer>, RunError> {
+    let values = match day {
+        1 => solve::<dec01::Dec01>(parts, input)?,
+        2 => solve::<dec02::Dec02>(parts, input)?,
+        3 => solve::<dec03::Dec03>(parts, input)?,
+        4 => solve::<dec04::Dec04>(parts, input)?,
+        5 => solve::<dec05::Dec05>(parts, input)?,
+        6 => solve::<dec06::Dec06>(parts, input)?,
+        7 => solve::<dec07::Dec07>(parts, input)?,
+        8 => solve::<dec08::Dec08>(parts, input)?,
+        9 => solve::<dec09::Dec09>(parts, input)?,
+        10 => solve::<dec10::Dec10>(parts, input)?,
+        11 => solve::<dec11::Dec11>(parts, input)?,
+        13 => solve::<dec13::Dec13>(parts, input)?,
+        14 => solve::<dec14::Dec14>(parts, input)?,
+        15 => solve::<dec15::Dec15>(parts, input)?,
+        _ => return Err(RunError::UnknownDay(day.to_string())),
+    };
+
+    Ok(parts
+        .iter()
+        .zip(values)
+        .map(|(&part, value)| Answer { day, part, value })
+        .collect())
+}
+
+fn solve<S: Solution>(parts: &[u32], input: &str) -> Result<Vec<Option<String>>, RunError> {
+    let input = S::parse(input).map_err(RunError::Parse)?;
+    Ok(parts
+        .iter()
+        .map(|part| match part {
+            1 => Some(S::part1(&input)),
+            _ => S::part2(&input),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_day() {
+        assert_eq!(Ok(1), parse_day("1"));
+        assert_eq!(Ok(7), parse_day("07"));
+        assert_eq!(Ok(15), parse_day("dec15"));
+        assert_eq!(Err(RunError::UnknownDay("12".to_string())), parse_day("12"));
+        assert_eq!(Err(RunError::UnknownDay("x".to_string())), parse_day("x"));
+    }
+
+    #[test]
+    fn can_parse_part() {
+        assert_eq!(Ok(2), parse_part("2"));
+        assert_eq!(Err(RunError::UnknownPart("3".to_string())), parse_part("3"));
+    }
+
+    #[test]
+    fn can_run_day() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        let answers = run(1, &[1, 2], input).unwrap();
+        assert_eq!(2, answers.len());
+        assert_eq!("dec01 part 1: 11", answers[0].to_string());
+        assert_eq!("dec01 part 2: 31", answers[1].to_string());
+    }
+
+    #[test]
+    fn can_run_single_part() {
+        let answers = run(9, &[2], "2333133121414131402").unwrap();
+        assert_eq!(
+            vec![Answer {
+                day: 9,
+                part: 2,
+                value: Some("2858".to_string())
+            }],
+            answers
+        );
+    }
+
+    #[test]
+    fn can_report_parse_errors() {
+        let error = run(2, &[1], "7 6 4\n1 x 3\n").unwrap_err();
+        assert_eq!(
+            "line 2, column 3: expected a number, found \"x\"",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn can_report_unsolved_part() {
+        let answers = run(8, &[2], "..\n..").unwrap();
+        assert_eq!("dec08 part 2: not solved yet", answers[0].to_string());
+    }
+}
@@ -0,0 +1,196 @@
+use std::env;
+use std::fs;
+use std::io::{stdin, Read};
+use std::path::PathBuf;
+use std::process;
+
+use aoc::inputs::{InputProvider, YEAR};
+use aoc::scaffold::{new_day, parse_new_day};
+use aoc::submit::Submitter;
+use aoc::{parse_day, parse_part, run, workspace, Answer, RunError};
+
+const USAGE: &str = "usage: aoc run <day> [--part 1|2] [--input PATH|-]
+       aoc submit <day> --part 1|2 [--answer ANSWER] [--input PATH|-]
+       aoc new <day>";
+
+#[derive(PartialEq)]
+enum Command {
+    Run,
+    Submit,
+    New,
+}
+
+struct Options {
+    command: Command,
+    day: u32,
+    parts: Vec<u32>,
+    input: Option<String>,
+    answer: Option<String>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if options.command == Command::New {
+        create(options.day);
+        return;
+    }
+
+    let answers = match &options.answer {
+        Some(answer) => vec![Answer {
+            day: options.day,
+            part: options.parts[0],
+            value: Some(answer.to_string()),
+        }],
+        None => solve(&options),
+    };
+
+    match options.command {
+        Command::Run => {
+            for answer in answers {
+                println!("{}", answer);
+            }
+        }
+        Command::Submit => submit(&answers[0]),
+        Command::New => unreachable!(),
+    }
+}
+
+fn create(day: u32) {
+    match new_day(&workspace::root(), day) {
+        Ok(folder) => {
+            println!("created {}", folder.display());
+            println!(
+                "add it to `DAYS` and `run` in aoc/src/lib.rs (and aoc/Cargo.toml) to run it with `cargo aoc run {}` and check its fixtures",
+                day
+            );
+        }
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    }
+}
+
+fn solve(options: &Options) -> Vec<Answer> {
+    let input = match read_input(options.day, options.input.as_deref()) {
+        Ok(input) => input,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+
+    match run(options.day, &options.parts, &input) {
+        Ok(answers) => answers,
+        Err(RunError::Parse(error)) => {
+            let source = match options.input.as_deref() {
+                Some("-") => "<stdin>".to_string(),
+                Some(path) => path.to_string(),
+                None => format!("input for day {}", options.day),
+            };
+            eprintln!("{}: {}", source, error);
+            process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn submit(answer: &Answer) {
+    let Some(value) = &answer.value else {
+        eprintln!("{}", answer);
+        process::exit(1);
+    };
+    let verdict = Submitter::from_env()
+        .and_then(|submitter| submitter.submit(YEAR, answer.day, answer.part, value));
+    match verdict {
+        Ok(verdict) => println!("{}, {}", answer, verdict),
+        Err(error) => {
+            eprintln!("{}, {}", answer, error);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut args = args.iter();
+    let command = match args.next().map(|a| a.as_str()) {
+        Some("run") => Command::Run,
+        Some("submit") => Command::Submit,
+        Some("new") => Command::New,
+        Some(command) => return Err(format!("unknown command {:?}", command)),
+        None => return Err("missing command".to_string()),
+    };
+
+    let day = args.next().ok_or("missing day")?;
+    let day = match command {
+        Command::New => parse_new_day(day)?,
+        _ => parse_day(day).map_err(|e| e.to_string())?,
+    };
+    let mut parts = vec![1, 2];
+    let mut input: Option<String> = None;
+    let mut answer: Option<String> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" if command != Command::New => {
+                let part = args.next().ok_or("--part needs a value")?;
+                parts = vec![parse_part(part).map_err(|e| e.to_string())?];
+            }
+            "--input" if command != Command::New => {
+                input = Some(args.next().ok_or("--input needs a value")?.to_string());
+            }
+            "--answer" if command == Command::Submit => {
+                answer = Some(args.next().ok_or("--answer needs a value")?.to_string());
+            }
+            _ => return Err(format!("unknown argument {:?}", arg)),
+        }
+    }
+    if command == Command::Submit && parts.len() != 1 {
+        return Err("submit needs a --part".to_string());
+    }
+
+    Ok(Options {
+        command,
+        day,
+        parts,
+        input,
+        answer,
+    })
+}
+
+// defaults to the `input.txt` in the day's package folder, or `inputs/2024/<day>.txt` which
+// is downloaded if it's not there yet. `-` reads from stdin.
+fn read_input(day: u32, input: Option<&str>) -> Result<String, String> {
+    let path = match input {
+        Some("-") => {
+            let mut buffer = String::new();
+            stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| format!("Failed to read input from stdin: {}", e))?;
+            return Ok(buffer);
+        }
+        Some(path) => PathBuf::from(path),
+        None => {
+            let path = workspace::root()
+                .join(format!("dec{:02}", day))
+                .join("input.txt");
+            if !path.exists() {
+                let provider = InputProvider::from_env();
+                return provider.get(YEAR, day).map_err(|e| e.to_string());
+            }
+            path
+        }
+    };
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::workspace;
+
+// `dec00` is the template for every new day, these are the files that are copied over.
+// `input.txt` and `target/` are left behind on purpose.
+pub const TEMPLATE: &str = "dec00";
+pub const TEMPLATE_FILES: [&str; 5] = [
+    "Cargo.toml",
+    "src/lib.rs",
+    "src/main.rs",
+    "fixtures/example.txt",
+    "fixtures/example.expected",
+];
+
+// accepts `12` and `dec12`, any day of the advent
+pub fn parse_new_day(day: &str) -> Result<u32, String> {
+    let number = day.strip_prefix("dec").unwrap_or(day);
+    match number.parse::<u32>() {
+        Ok(n) if (1..=25).contains(&n) => Ok(n),
+        _ => Err(format!("day must be between 1 and 25, not {:?}", day)),
+    }
+}
+
+// `dec00` becomes `dec12` and `Dec00` becomes `Dec12`
+pub fn render(template: &str, day: u32) -> String {
+    template
+        .replace(TEMPLATE, &format!("dec{:02}", day))
+        .replace("Dec00", &format!("Dec{:02}", day))
+}
+
+// creates `decNN` from the template and adds it to the workspace members. refuses to touch a
+// day that already exists, nothing is written in that case.
+pub fn new_day(root: &Path, day: u32) -> Result<PathBuf, String> {
+    let name = format!("dec{:02}", day);
+    let folder = root.join(&name);
+    if folder.exists() {
+        return Err(format!("{} already exists", folder.display()));
+    }
+    if workspace::members(root)?.contains(&name) {
+        return Err(format!("{} is already a workspace member", name));
+    }
+
+    let template = root.join(TEMPLATE);
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+    for file in TEMPLATE_FILES {
+        let path = template.join(file);
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        files.push((folder.join(file), render(&content, day)));
+    }
+
+    let created = write_files(files).and_then(|_| workspace::add_member(root, &name));
+    if let Err(message) = created {
+        // a half created day would only make the next attempt fail with "already exists"
+        let _ = fs::remove_dir_all(&folder);
+        return Err(message);
+    }
+    Ok(folder)
+}
+
+fn write_files(files: Vec<(PathBuf, String)>) -> Result<(), String> {
+    for (path, content) in files {
+        fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| fs::write(&path, content))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_new_day() {
+        assert_eq!(Ok(12), parse_new_day("12"));
+        assert_eq!(Ok(3), parse_new_day("dec03"));
+        assert!(parse_new_day("0").is_err());
+        assert!(parse_new_day("26").is_err());
+    }
+
+    #[test]
+    fn can_render_template() {
+        let template = "use dec00::Dec00;\n\nfn main() {\n    Dec00::parse(\"\");\n}\n";
+        assert_eq!(
+            "use dec12::Dec12;\n\nfn main() {\n    Dec12::parse(\"\");\n}\n",
+            render(template, 12)
+        );
+    }
+}
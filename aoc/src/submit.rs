@@ -0,0 +1,405 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::inputs::{HttpBackend, USER_AGENT};
+use crate::workspace;
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    // wrong, but the site didn't say in which direction
+    Wrong,
+    // the last answer was submitted too recently, seconds left to wait
+    Wait(u64),
+    // the site didn't check the answer, the part is either solved already or still locked
+    // because the one before isn't. says nothing about the answer, so it doesn't block anything.
+    #[serde(alias = "already_solved")]
+    WrongLevel,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::Wait(seconds) => write!(f, "not checked, wait {}s", seconds),
+            Verdict::WrongLevel => write!(
+                f,
+                "not checked, the part is solved already or not unlocked yet"
+            ),
+        }
+    }
+}
+
+pub fn parse_verdict(response: &str) -> Option<Verdict> {
+    if response.contains("That's the right answer") {
+        Some(Verdict::Correct)
+    } else if response.contains("You gave an answer too recently") {
+        Some(Verdict::Wait(parse_wait(response).unwrap_or(60)))
+    } else if response.contains("That's not the right answer") {
+        if response.contains("too high") {
+            Some(Verdict::TooHigh)
+        } else if response.contains("too low") {
+            Some(Verdict::TooLow)
+        } else {
+            Some(Verdict::Wrong)
+        }
+    } else if response.contains("Did you already complete it") {
+        Some(Verdict::WrongLevel)
+    } else {
+        None
+    }
+}
+
+// "You have 1m 3s left to wait."
+fn parse_wait(response: &str) -> Option<u64> {
+    let end = response.find(" left to wait")?;
+    let start = response[..end].rfind("have ")? + "have ".len();
+    response[start..end]
+        .split_whitespace()
+        .map(|amount| {
+            let (number, unit) = amount.split_at(amount.len() - 1);
+            let number: u64 = number.parse().ok()?;
+            match unit {
+                "h" => Some(number * 3600),
+                "m" => Some(number * 60),
+                "s" => Some(number),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Attempt {
+    pub part: u32,
+    pub answer: String,
+    pub verdict: Verdict,
+    // seconds since the unix epoch
+    pub submitted_at: u64,
+}
+
+// reasons not to bother the site with an answer
+#[derive(PartialEq, Debug)]
+pub enum Refusal {
+    // with the correct answer
+    AlreadySolved(String),
+    KnownWrong(Verdict),
+    // an answer at least as high as this one was too high already
+    AboveBound(String),
+    // an answer at most as low as this one was too low already
+    BelowBound(String),
+    // seconds left to wait
+    Wait(u64),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::AlreadySolved(answer) => {
+                write!(f, "the part is solved already, the answer was {}", answer)
+            }
+            Refusal::KnownWrong(verdict) => {
+                write!(f, "the answer was submitted before and was {}", verdict)
+            }
+            Refusal::AboveBound(bound) => {
+                write!(f, "{} was too high already", bound)
+            }
+            Refusal::BelowBound(bound) => write!(f, "{} was too low already", bound),
+            Refusal::Wait(seconds) => {
+                write!(f, "the site asked to wait, try again in {}s", seconds)
+            }
+        }
+    }
+}
+
+// every answer ever submitted for one day, kept as json next to the input
+#[derive(PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    pub attempts: Vec<Attempt>,
+}
+
+impl History {
+    pub fn load(path: &Path) -> Result<History, SubmitError> {
+        if !path.exists() {
+            return Ok(History::default());
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| SubmitError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| SubmitError::Io(format!("Failed to parse {}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SubmitError> {
+        let folder = path.parent().unwrap();
+        fs::create_dir_all(folder).map_err(|e| {
+            SubmitError::Io(format!("Failed to create {}: {}", folder.display(), e))
+        })?;
+        let content = serde_json::to_string_pretty(self).unwrap();
+        fs::write(path, content + "\n")
+            .map_err(|e| SubmitError::Io(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    pub fn check(&self, part: u32, answer: &str, now: u64) -> Result<(), Refusal> {
+        let attempts: Vec<&Attempt> = self.attempts.iter().filter(|a| a.part == part).collect();
+
+        if let Some(attempt) = attempts.iter().find(|a| a.verdict == Verdict::Correct) {
+            return Err(Refusal::AlreadySolved(attempt.answer.clone()));
+        }
+
+        // the site's timeout holds for every part and day, but this is all we know about
+        if let Some(last) = self.attempts.last() {
+            if let Verdict::Wait(seconds) = last.verdict {
+                let until = last.submitted_at + seconds;
+                if now < until {
+                    return Err(Refusal::Wait(until - now));
+                }
+            }
+        }
+
+        // answers the site didn't check can be tried again
+        let known = attempts.iter().find(|a| {
+            a.answer == answer && !matches!(a.verdict, Verdict::Wait(_) | Verdict::WrongLevel)
+        });
+        if let Some(attempt) = known {
+            return Err(Refusal::KnownWrong(attempt.verdict.clone()));
+        }
+
+        if let Ok(number) = answer.parse::<i128>() {
+            let numbers = |verdict: Verdict| {
+                attempts
+                    .iter()
+                    .filter(move |a| a.verdict == verdict)
+                    .filter_map(|a| Some((a.answer.parse::<i128>().ok()?, a.answer.as_str())))
+            };
+            if let Some((high, text)) = numbers(Verdict::TooHigh).min() {
+                if number >= high {
+                    return Err(Refusal::AboveBound(text.to_string()));
+                }
+            }
+            if let Some((low, text)) = numbers(Verdict::TooLow).max() {
+                if number <= low {
+                    return Err(Refusal::BelowBound(text.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum SubmitError {
+    Refused(Refusal),
+    NoSession,
+    // the response didn't look like any verdict we know
+    UnknownVerdict(String),
+    Http(u16, String),
+    Request(String),
+    Io(String),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Refused(refusal) => write!(f, "not submitted, {}", refusal),
+            SubmitError::NoSession => write!(
+                f,
+                "set AOC_SESSION or put your session cookie in .aoc-session to submit answers"
+            ),
+            SubmitError::UnknownVerdict(response) => {
+                write!(f, "could not make sense of the response: {}", response)
+            }
+            SubmitError::Http(status, message) => {
+                write!(f, "submitting failed with {}: {}", status, message)
+            }
+            SubmitError::Request(message) => write!(f, "submitting failed: {}", message),
+            SubmitError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+pub trait Endpoint {
+    // posts the answer and returns the response page
+    fn submit(&self, year: u32, day: u32, part: u32, answer: &str) -> Result<String, SubmitError>;
+}
+
+impl Endpoint for HttpBackend {
+    fn submit(&self, year: u32, day: u32, part: u32, answer: &str) -> Result<String, SubmitError> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+        match response {
+            Ok(response) => response
+                .into_string()
+                .map_err(|e| SubmitError::Request(e.to_string())),
+            Err(ureq::Error::Status(status, response)) => {
+                let message = response.into_string().unwrap_or_default();
+                Err(SubmitError::Http(status, message.trim().to_string()))
+            }
+            Err(error) => Err(SubmitError::Request(error.to_string())),
+        }
+    }
+}
+
+// submits answers and keeps the verdicts in `<root>/<year>/<day>.answers.json`
+pub struct Submitter {
+    root: PathBuf,
+    endpoint: Box<dyn Endpoint>,
+}
+
+impl Submitter {
+    pub fn new(root: impl Into<PathBuf>, endpoint: impl Endpoint + 'static) -> Submitter {
+        Submitter {
+            root: root.into(),
+            endpoint: Box::new(endpoint),
+        }
+    }
+
+    // keeps the history in `inputs/` and submits with `HttpBackend::from_env`
+    pub fn from_env() -> Result<Submitter, SubmitError> {
+        let backend = HttpBackend::from_env().ok_or(SubmitError::NoSession)?;
+        Ok(Submitter::new(workspace::root().join("inputs"), backend))
+    }
+
+    pub fn history_path(&self, year: u32, day: u32) -> PathBuf {
+        self.root
+            .join(year.to_string())
+            .join(format!("{:02}.answers.json", day))
+    }
+
+    pub fn history(&self, year: u32, day: u32) -> Result<History, SubmitError> {
+        History::load(&self.history_path(year, day))
+    }
+
+    pub fn submit(
+        &self,
+        year: u32,
+        day: u32,
+        part: u32,
+        answer: &str,
+    ) -> Result<Verdict, SubmitError> {
+        let path = self.history_path(year, day);
+        let mut history = History::load(&path)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        history
+            .check(part, answer, now)
+            .map_err(SubmitError::Refused)?;
+
+        let response = self.endpoint.submit(year, day, part, answer)?;
+        let verdict = parse_verdict(&response)
+            .ok_or_else(|| SubmitError::UnknownVerdict(response.chars().take(200).collect()))?;
+
+        history.attempts.push(Attempt {
+            part,
+            answer: answer.to_string(),
+            verdict: verdict.clone(),
+            submitted_at: now,
+        });
+        history.save(&path)?;
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(part: u32, answer: &str, verdict: Verdict) -> Attempt {
+        Attempt {
+            part,
+            answer: answer.to_string(),
+            verdict,
+            submitted_at: 1000,
+        }
+    }
+
+    #[test]
+    fn can_parse_verdicts() {
+        let response =
+            "<article><p>That's the right answer! You are one gold star closer.</p></article>";
+        assert_eq!(Some(Verdict::Correct), parse_verdict(response));
+        let response = "<p>That's not the right answer; your answer is too high.  If you're stuck";
+        assert_eq!(Some(Verdict::TooHigh), parse_verdict(response));
+        let response = "<p>That's not the right answer; your answer is too low.";
+        assert_eq!(Some(Verdict::TooLow), parse_verdict(response));
+        let response = "<p>That's not the right answer.  If you're stuck";
+        assert_eq!(Some(Verdict::Wrong), parse_verdict(response));
+        let response = "<p>You gave an answer too recently. You have 1m 3s left to wait.</p>";
+        assert_eq!(Some(Verdict::Wait(63)), parse_verdict(response));
+        let response =
+            "<p>You don't seem to be solving the right level.  Did you already complete it?";
+        assert_eq!(Some(Verdict::WrongLevel), parse_verdict(response));
+        assert_eq!(None, parse_verdict("<p>Puzzle inputs differ by user.</p>"));
+    }
+
+    #[test]
+    fn can_refuse_pointless_answers() {
+        let history = History {
+            attempts: vec![
+                attempt(1, "120", Verdict::TooHigh),
+                attempt(1, "80", Verdict::TooLow),
+                attempt(1, "abc", Verdict::Wrong),
+                attempt(2, "42", Verdict::Correct),
+            ],
+        };
+        assert_eq!(Ok(()), history.check(1, "100", 2000));
+        assert_eq!(
+            Err(Refusal::AboveBound("120".to_string())),
+            history.check(1, "150", 2000)
+        );
+        assert_eq!(
+            Err(Refusal::BelowBound("80".to_string())),
+            history.check(1, "70", 2000)
+        );
+        assert_eq!(
+            Err(Refusal::KnownWrong(Verdict::Wrong)),
+            history.check(1, "abc", 2000)
+        );
+        assert_eq!(
+            Err(Refusal::AlreadySolved("42".to_string())),
+            history.check(2, "43", 2000)
+        );
+    }
+
+    #[test]
+    fn can_retry_after_wrong_level() {
+        let history = History {
+            attempts: vec![attempt(2, "42", Verdict::WrongLevel)],
+        };
+        assert_eq!(Ok(()), history.check(2, "42", 2000));
+        assert_eq!(Ok(()), history.check(2, "43", 2000));
+
+        let history: History = serde_json::from_str(
+            r#"{"attempts": [{"part": 2, "answer": "42", "verdict": "already_solved", "submitted_at": 1000}]}"#,
+        )
+        .unwrap();
+        assert_eq!(Verdict::WrongLevel, history.attempts[0].verdict);
+    }
+
+    #[test]
+    fn can_refuse_while_waiting() {
+        let history = History {
+            attempts: vec![attempt(1, "100", Verdict::Wait(60))],
+        };
+        assert_eq!(Err(Refusal::Wait(20)), history.check(1, "100", 1040));
+        assert_eq!(Ok(()), history.check(1, "100", 1060));
+    }
+}
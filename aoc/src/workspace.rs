@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+// reads the `members = [ ... ]` list of the workspace manifest
+pub fn members(root: &Path) -> Result<Vec<String>, String> {
+    let path = root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_members(&manifest).ok_or(format!("{} has no members list", path.display()))
+}
+
+// adds a member to the workspace manifest, the list is written back sorted
+pub fn add_member(root: &Path, member: &str) -> Result<(), String> {
+    let path = root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut members =
+        parse_members(&manifest).ok_or(format!("{} has no members list", path.display()))?;
+    if members.iter().any(|m| m == member) {
+        return Err(format!("{} is already a workspace member", member));
+    }
+    members.push(member.to_string());
+    members.sort();
+    let manifest = replace_members(&manifest, &members).unwrap();
+    fs::write(&path, manifest).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// the byte range between the brackets of the members list
+fn members_span(manifest: &str) -> Option<(usize, usize)> {
+    let start = manifest.find("members")?;
+    let open = start + manifest[start..].find('[')? + 1;
+    let close = start + manifest[start..].find(']')?;
+    Some((open, close))
+}
+
+fn parse_members(manifest: &str) -> Option<Vec<String>> {
+    let (open, close) = members_span(manifest)?;
+    Some(
+        manifest[open..close]
+            .split(',')
+            .map(|member| member.trim().trim_matches('"').to_string())
+            .filter(|member| !member.is_empty())
+            .collect(),
+    )
+}
+
+fn replace_members(manifest: &str, members: &[String]) -> Option<String> {
+    let (open, close) = members_span(manifest)?;
+    let list: Vec<String> = members.iter().map(|m| format!("{:?}", m)).collect();
+    Some(format!(
+        "{} {}{}",
+        &manifest[..open],
+        list.join(", "),
+        &manifest[close..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_members() {
+        let manifest = "[workspace]\nmembers = [ \"aoc\", \"dec00\",\"dec01\",\n  \"dec02\"]\nresolver = \"2\"\n";
+        assert_eq!(
+            Some(vec![
+                "aoc".to_string(),
+                "dec00".to_string(),
+                "dec01".to_string(),
+                "dec02".to_string()
+            ]),
+            parse_members(manifest)
+        );
+        assert_eq!(None, parse_members("[package]\nname = \"aoc\"\n"));
+    }
+
+    #[test]
+    fn can_replace_members() {
+        let manifest = "[workspace]\nmembers = [ \"aoc\",\"dec01\"]\nresolver = \"2\"\n";
+        let members = vec!["aoc".to_string(), "dec01".to_string(), "dec02".to_string()];
+        assert_eq!(
+            Some(
+                "[workspace]\nmembers = [ \"aoc\", \"dec01\", \"dec02\"]\nresolver = \"2\"\n"
+                    .to_string()
+            ),
+            replace_members(manifest, &members)
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeSet;
+
+use aoc::fixtures::{check, discover, unregistered, Outcome};
+use aoc::{workspace, DAYS};
+
+// runs every `decNN/fixtures/*.txt` and compares the answers with its `.expected` file.
+// `cargo test -p aoc --test fixtures -- --nocapture` prints the report even if everything passes.
+#[test]
+fn fixtures_match_expected_answers() {
+    let fixtures = discover(&workspace::root()).unwrap();
+    let outcomes: Vec<Outcome> = fixtures.iter().map(check).collect();
+
+    let mut report: Vec<String> = outcomes.iter().map(|o| o.to_string()).collect();
+    let covered: BTreeSet<u32> = outcomes.iter().map(|o| o.day).collect();
+    for day in DAYS.iter().filter(|day| !covered.contains(day)) {
+        report.push(format!("dec{:02}: no fixtures", day));
+    }
+    let failed = outcomes.iter().filter(|o| !o.passed()).count();
+    report.push(format!(
+        "{} fixtures, {} passed, {} failed",
+        outcomes.len(),
+        outcomes.len() - failed,
+        failed
+    ));
+    let report = report.join("\n");
+
+    println!("{}", report);
+    assert!(!fixtures.is_empty(), "no fixtures found");
+    assert_eq!(0, failed, "\n{}", report);
+
+    let unregistered = unregistered(&workspace::root()).unwrap();
+    assert!(
+        unregistered.is_empty(),
+        "the fixtures of {} are not checked, add them to `DAYS` and `run` in aoc/src/lib.rs",
+        unregistered.join(", ")
+    );
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
+use aoc::inputs::{HttpBackend, InputError, InputProvider};
+
+mod stub;
+use stub::{temp_dir, Stub};
+
+#[test]
+fn can_read_cached_input() {
+    let root = temp_dir("cached-input");
+    fs::create_dir_all(root.join("2024")).unwrap();
+    fs::write(root.join("2024/07.txt"), "190: 10 19\n").unwrap();
+
+    let provider = InputProvider::new(&root);
+    assert_eq!(Ok("190: 10 19\n".to_string()), provider.get(2024, 7));
+}
+
+#[test]
+fn can_report_missing_session() {
+    let root = temp_dir("missing-session");
+    let provider = InputProvider::new(&root);
+    assert_eq!(
+        Err(InputError::NotCached(root.join("2024/03.txt"))),
+        provider.get(2024, 3)
+    );
+}
+
+#[test]
+fn can_download_and_cache_input() {
+    let root = temp_dir("download-input");
+    let stub = Stub::start(vec![(200, "125 17\n")]);
+    let provider = InputProvider::new(&root)
+        .with_backend(HttpBackend::new(&stub.url, "cookie"))
+        .with_min_interval(Duration::ZERO);
+
+    assert_eq!(Ok("125 17\n".to_string()), provider.get(2024, 11));
+    // the second time it comes from the cache, the stub would not answer anymore
+    assert_eq!(Ok("125 17\n".to_string()), provider.get(2024, 11));
+    assert_eq!(
+        "125 17\n",
+        fs::read_to_string(root.join("2024/11.txt")).unwrap()
+    );
+
+    let requests = stub.requests();
+    assert_eq!(1, requests.len());
+    assert_eq!("GET", requests[0].method);
+    assert_eq!("/2024/day/11/input", requests[0].path);
+    assert_eq!(Some("session=cookie"), requests[0].header("cookie"));
+}
+
+#[test]
+fn can_report_http_errors() {
+    let root = temp_dir("http-errors");
+    let stub = Stub::start(vec![(404, "Not Found")]);
+    let provider = InputProvider::new(&root)
+        .with_backend(HttpBackend::new(&stub.url, "cookie"))
+        .with_min_interval(Duration::ZERO);
+
+    assert_eq!(
+        Err(InputError::Http(404, "Not Found".to_string())),
+        provider.get(2024, 25)
+    );
+    assert!(!root.join("2024/25.txt").exists());
+}
+
+#[test]
+fn can_limit_request_rate() {
+    let root = temp_dir("rate-limit");
+    let stub = Stub::start(vec![(200, "1"), (200, "2")]);
+    let provider = InputProvider::new(&root)
+        .with_backend(HttpBackend::new(&stub.url, "cookie"))
+        .with_min_interval(Duration::from_millis(300));
+
+    let start = Instant::now();
+    provider.get(2024, 1).unwrap();
+    provider.get(2024, 2).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(300));
+    assert_eq!(2, stub.requests().len());
+}
@@ -0,0 +1,75 @@
+use std::fs;
+
+use aoc::fixtures::unregistered;
+use aoc::scaffold::{new_day, TEMPLATE, TEMPLATE_FILES};
+use aoc::workspace;
+
+mod stub;
+use stub::temp_dir;
+
+// a workspace with a copy of the real template
+fn workspace_with_template(name: &str) -> std::path::PathBuf {
+    let root = temp_dir(name);
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [ \"aoc\", \"dec00\",\"dec01\", \"dec13\"]\nresolver = \"2\"\n",
+    )
+    .unwrap();
+    for file in TEMPLATE_FILES {
+        let path = root.join(TEMPLATE).join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::copy(workspace::root().join(TEMPLATE).join(file), path).unwrap();
+    }
+    root
+}
+
+#[test]
+fn can_create_new_day() {
+    let root = workspace_with_template("new-day");
+
+    assert_eq!(Ok(root.join("dec12")), new_day(&root, 12));
+    for file in TEMPLATE_FILES {
+        assert!(
+            root.join("dec12").join(file).exists(),
+            "{} is missing",
+            file
+        );
+    }
+    let lib = fs::read_to_string(root.join("dec12/src/lib.rs")).unwrap();
+    assert!(lib.contains("pub struct Dec12;"));
+    assert!(!lib.contains("Dec00"));
+    let manifest = fs::read_to_string(root.join("dec12/Cargo.toml")).unwrap();
+    assert!(manifest.contains("name = \"dec12\""));
+
+    assert_eq!(
+        Ok(vec![
+            "aoc".to_string(),
+            "dec00".to_string(),
+            "dec01".to_string(),
+            "dec12".to_string(),
+            "dec13".to_string()
+        ]),
+        workspace::members(&root)
+    );
+    // its fixtures aren't checked until it's added to `DAYS`
+    assert_eq!(Ok(vec!["dec12".to_string()]), unregistered(&root));
+}
+
+#[test]
+fn can_refuse_to_overwrite_day() {
+    let root = workspace_with_template("existing-day");
+    fs::create_dir_all(root.join("dec01/src")).unwrap();
+    fs::write(root.join("dec01/src/lib.rs"), "// solved already\n").unwrap();
+
+    let error = new_day(&root, 1).unwrap_err();
+    assert!(error.ends_with("dec01 already exists"), "{}", error);
+    assert_eq!(
+        "// solved already\n",
+        fs::read_to_string(root.join("dec01/src/lib.rs")).unwrap()
+    );
+
+    // listed as a member, but the folder is gone
+    let error = new_day(&root, 13).unwrap_err();
+    assert_eq!("dec13 is already a workspace member", error);
+    assert!(!root.join("dec13").exists());
+}
@@ -0,0 +1,97 @@
+use aoc::inputs::HttpBackend;
+use aoc::submit::{History, Refusal, SubmitError, Submitter, Verdict};
+
+mod stub;
+use stub::{temp_dir, Stub};
+
+const TOO_HIGH: &str =
+    "<article><p>That's not the right answer; your answer is too high.</p></article>";
+const WRONG_LEVEL: &str =
+    "<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>";
+const CORRECT: &str =
+    "<article><p>That's the right answer! You are one gold star closer.</p></article>";
+
+#[test]
+fn can_submit_answers_and_keep_history() {
+    let root = temp_dir("submit-answers");
+    let stub = Stub::start(vec![(200, TOO_HIGH), (200, CORRECT)]);
+    let submitter = Submitter::new(&root, HttpBackend::new(&stub.url, "cookie"));
+
+    assert_eq!(Ok(Verdict::TooHigh), submitter.submit(2024, 7, 1, "5000"));
+    assert_eq!(Ok(Verdict::Correct), submitter.submit(2024, 7, 1, "3749"));
+
+    let requests = stub.requests();
+    assert_eq!(2, requests.len());
+    assert_eq!("POST", requests[0].method);
+    assert_eq!("/2024/day/7/answer", requests[0].path);
+    assert_eq!(Some("session=cookie"), requests[0].header("cookie"));
+    assert_eq!("level=1&answer=5000", requests[0].body);
+
+    let history: History = submitter.history(2024, 7).unwrap();
+    let verdicts: Vec<(&str, &Verdict)> = history
+        .attempts
+        .iter()
+        .map(|a| (a.answer.as_str(), &a.verdict))
+        .collect();
+    assert_eq!(
+        vec![("5000", &Verdict::TooHigh), ("3749", &Verdict::Correct)],
+        verdicts
+    );
+    assert!(submitter
+        .history_path(2024, 7)
+        .ends_with("2024/07.answers.json"));
+}
+
+#[test]
+fn can_refuse_without_asking_the_site() {
+    let root = temp_dir("submit-refusals");
+    let stub = Stub::start(vec![(200, TOO_HIGH)]);
+    let submitter = Submitter::new(&root, HttpBackend::new(&stub.url, "cookie"));
+
+    submitter.submit(2024, 9, 2, "100").unwrap();
+    assert_eq!(
+        Err(SubmitError::Refused(Refusal::KnownWrong(Verdict::TooHigh))),
+        submitter.submit(2024, 9, 2, "100")
+    );
+    assert_eq!(
+        Err(SubmitError::Refused(Refusal::AboveBound("100".to_string()))),
+        submitter.submit(2024, 9, 2, "250")
+    );
+    assert_eq!(1, stub.requests().len());
+}
+
+#[test]
+fn can_submit_after_submitting_too_early() {
+    let root = temp_dir("submit-too-early");
+    let stub = Stub::start(vec![(200, WRONG_LEVEL), (200, CORRECT)]);
+    let submitter = Submitter::new(&root, HttpBackend::new(&stub.url, "cookie"));
+
+    // part 2 before part 1 is solved
+    assert_eq!(
+        Ok(Verdict::WrongLevel),
+        submitter.submit(2024, 4, 2, "1900")
+    );
+    assert_eq!(Ok(Verdict::Correct), submitter.submit(2024, 4, 2, "1900"));
+    assert_eq!(2, stub.requests().len());
+    assert_eq!(
+        Err(SubmitError::Refused(Refusal::AlreadySolved(
+            "1900".to_string()
+        ))),
+        submitter.submit(2024, 4, 2, "1901")
+    );
+}
+
+#[test]
+fn can_report_unknown_responses() {
+    let root = temp_dir("submit-unknown");
+    let stub = Stub::start(vec![(200, "<html>maintenance</html>")]);
+    let submitter = Submitter::new(&root, HttpBackend::new(&stub.url, "cookie"));
+
+    assert_eq!(
+        Err(SubmitError::UnknownVerdict(
+            "<html>maintenance</html>".to_string()
+        )),
+        submitter.submit(2024, 1, 1, "11")
+    );
+    assert_eq!(History::default(), submitter.history(2024, 1).unwrap());
+}
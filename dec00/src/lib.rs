@@ -1,5 +1,25 @@
-pub fn do_something() -> bool {
-    true
+use aoc_core::{ParseError, Solution};
+
+pub fn do_something(lines: &[String]) -> usize {
+    lines.len()
+}
+
+pub struct Dec00;
+
+impl Solution for Dec00 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+        Ok(input.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part1(lines: &Vec<String>) -> String {
+        do_something(lines).to_string()
+    }
+
+    fn part2(_lines: &Vec<String>) -> Option<String> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -8,6 +28,15 @@ mod tests {
 
     #[test]
     fn can_do_something() {
-        assert!(do_something());
+        let lines = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(2, do_something(&lines));
+    }
+
+    #[test]
+    fn can_parse_input() {
+        assert_eq!(
+            Ok(vec!["a".to_string(), "b".to_string()]),
+            Dec00::parse("a\nb\n")
+        );
     }
 }
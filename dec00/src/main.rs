@@ -1,11 +1,24 @@
 use std::fs;
 
-use dec00::do_something;
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec00::Dec00;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    for line in input.lines() {
-        println!("{:?}", line);
+    let parsed = stats.measure("parse", || Dec00::parse(&input).or_exit("input.txt"));
+
+    let answer = stats.measure("part 1", || Dec00::part1(&parsed));
+    println!("part 1: {}", answer);
+
+    let answer = stats.measure("part 2", || Dec00::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("part 2: {}", answer);
     }
-    do_something();
+
+    stats.report();
 }
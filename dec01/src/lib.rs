@@ -1,16 +1,85 @@
-pub fn difference_vector(left: &[i32], right: &[i32]) -> Vec<i32> {
-    let mut sorted_left: Vec<i32> = left.to_vec();
-    let mut sorted_right: Vec<i32> = right.to_vec();
-    let mut result = Vec::new();
-    sorted_left.sort();
-    sorted_right.sort();
+use std::collections::HashMap;
 
-    for i in 0..sorted_left.len() {
-        let difference = sorted_left[i] - sorted_right[i];
-        result.push(difference.abs());
+use aoc_core::columns::ColumnReader;
+use aoc_core::{ParseError, Solution};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Metric {
+    Absolute,
+    Squared,
+    // left minus right, so the direction of the difference is kept
+    Signed,
+    // 1 if the numbers at the same place in sorted order differ, 0 otherwise
+    Hamming,
+}
+
+impl Metric {
+    // i128 so even the square of the largest difference between two i32 fits
+    pub fn distance(&self, left: i32, right: i32) -> i128 {
+        let difference = left as i128 - right as i128;
+        match self {
+            Metric::Absolute => difference.abs(),
+            Metric::Squared => difference * difference,
+            Metric::Signed => difference,
+            Metric::Hamming => (difference != 0) as i128,
+        }
+    }
+}
+
+// both lists are paired up in sorted order. whatever is left of the longer list once the
+// shorter one runs out has no partner and ends up in `unmatched_left` or `unmatched_right`.
+#[derive(PartialEq, Eq, Debug)]
+pub struct Comparison {
+    pub distances: Vec<i128>,
+    pub unmatched_left: Vec<i32>,
+    pub unmatched_right: Vec<i32>,
+}
+
+impl Comparison {
+    pub fn total(&self) -> i128 {
+        self.distances.iter().sum()
     }
 
-    result
+    pub fn is_complete(&self) -> bool {
+        self.unmatched_left.is_empty() && self.unmatched_right.is_empty()
+    }
+}
+
+pub fn compare_lists(
+    left: impl IntoIterator<Item = i32>,
+    right: impl IntoIterator<Item = i32>,
+    metric: Metric,
+) -> Comparison {
+    let mut sorted_left: Vec<i32> = left.into_iter().collect();
+    let mut sorted_right: Vec<i32> = right.into_iter().collect();
+    sorted_left.sort_unstable();
+    sorted_right.sort_unstable();
+
+    let paired = sorted_left.len().min(sorted_right.len());
+    let distances = sorted_left
+        .iter()
+        .zip(&sorted_right)
+        .map(|(&l, &r)| metric.distance(l, r))
+        .collect();
+    Comparison {
+        distances,
+        unmatched_left: sorted_left.split_off(paired),
+        unmatched_right: sorted_right.split_off(paired),
+    }
+}
+
+// the absolute differences of the paired up numbers, see `compare_lists` for the ones that
+// have no partner. two i32 are at most `u32::MAX` apart.
+pub fn difference_vector(left: &[i32], right: &[i32]) -> Vec<u32> {
+    compare_lists(
+        left.iter().copied(),
+        right.iter().copied(),
+        Metric::Absolute,
+    )
+    .distances
+    .into_iter()
+    .map(|distance| u32::try_from(distance).expect("two i32 are at most u32::MAX apart"))
+    .collect()
 }
 
 pub fn similarity_score(number: i32, vector: &[i32]) -> i32 {
@@ -23,10 +92,83 @@ pub fn similarity_score(number: i32, vector: &[i32]) -> i32 {
     frequency * number
 }
 
+// how often every number shows up in a list. built once, it answers similarity scores without
+// going through the list again.
+#[derive(Debug, Default)]
+pub struct FrequencyIndex {
+    counts: HashMap<i32, usize>,
+}
+
+impl FrequencyIndex {
+    pub fn new(numbers: impl IntoIterator<Item = i32>) -> FrequencyIndex {
+        let mut counts: HashMap<i32, usize> = HashMap::new();
+        for number in numbers {
+            *counts.entry(number).or_insert(0) += 1;
+        }
+        FrequencyIndex { counts }
+    }
+
+    pub fn count(&self, number: i32) -> usize {
+        self.counts.get(&number).copied().unwrap_or(0)
+    }
+
+    // same as `similarity_score`, but in constant time
+    pub fn similarity(&self, number: i32) -> i64 {
+        number as i64 * self.count(number) as i64
+    }
+}
+
+impl FromIterator<i32> for FrequencyIndex {
+    fn from_iter<I: IntoIterator<Item = i32>>(numbers: I) -> FrequencyIndex {
+        FrequencyIndex::new(numbers)
+    }
+}
+
+pub fn total_similarity(
+    left: impl IntoIterator<Item = i32>,
+    right: impl IntoIterator<Item = i32>,
+) -> i64 {
+    let index = FrequencyIndex::new(right);
+    left.into_iter()
+        .map(|number| index.similarity(number))
+        .sum()
+}
+
 pub fn sum_up_vector(vector: &[i32]) -> i32 {
     vector.iter().sum()
 }
 
+pub struct Dec01;
+
+pub struct LocationLists {
+    pub left: Vec<i32>,
+    pub right: Vec<i32>,
+}
+
+impl Solution for Dec01 {
+    type Input = LocationLists;
+
+    fn parse(input: &str) -> Result<LocationLists, ParseError> {
+        let pairs: Vec<(i32, i32)> = ColumnReader::new().read(input)?;
+        let (left, right) = pairs.into_iter().unzip();
+        Ok(LocationLists { left, right })
+    }
+
+    fn part1(lists: &LocationLists) -> String {
+        let comparison = compare_lists(
+            lists.left.iter().copied(),
+            lists.right.iter().copied(),
+            Metric::Absolute,
+        );
+        comparison.total().to_string()
+    }
+
+    fn part2(lists: &LocationLists) -> Option<String> {
+        let total = total_similarity(lists.left.iter().copied(), lists.right.iter().copied());
+        Some(total.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,6 +179,45 @@ mod tests {
         let right = vec![4, 3, 5, 3, 9, 3];
         let result = vec![2, 1, 0, 1, 2, 5];
         assert_eq!(result, difference_vector(&left, &right));
+        assert_eq!(vec![u32::MAX], difference_vector(&[i32::MAX], &[i32::MIN]));
+    }
+
+    #[test]
+    fn can_compare_lists_of_different_length() {
+        let comparison = compare_lists(vec![3, 4, 2, 1], vec![4, 3], Metric::Absolute);
+        assert_eq!(vec![2, 2], comparison.distances);
+        assert_eq!(vec![3, 4], comparison.unmatched_left);
+        assert!(comparison.unmatched_right.is_empty());
+        assert!(!comparison.is_complete());
+
+        let comparison = compare_lists([5], (1..10).step_by(4), Metric::Absolute);
+        assert_eq!(vec![4], comparison.distances);
+        assert!(comparison.unmatched_left.is_empty());
+        assert_eq!(vec![5, 9], comparison.unmatched_right);
+
+        let comparison = compare_lists(vec![], vec![], Metric::Absolute);
+        assert_eq!(0, comparison.total());
+        assert!(comparison.is_complete());
+    }
+
+    #[test]
+    fn can_compare_lists_with_metrics() {
+        let left = vec![3, 4, 2, 1, 3, 3];
+        let right = vec![4, 3, 5, 3, 9, 3];
+        let total = |metric| compare_lists(left.clone(), right.clone(), metric).total();
+        assert_eq!(11, total(Metric::Absolute));
+        assert_eq!(35, total(Metric::Squared));
+        assert_eq!(-11, total(Metric::Signed));
+        assert_eq!(5, total(Metric::Hamming));
+        assert_eq!(40_000_000_000, Metric::Squared.distance(100_000, -100_000));
+        assert_eq!(
+            (u32::MAX as i128).pow(2),
+            Metric::Squared.distance(i32::MIN, i32::MAX)
+        );
+        assert_eq!(
+            -(u32::MAX as i128),
+            Metric::Signed.distance(i32::MIN, i32::MAX)
+        );
     }
 
     #[test]
@@ -53,4 +234,36 @@ mod tests {
         assert_eq!(0, similarity_score(2, &vector));
         assert_eq!(0, similarity_score(1, &vector));
     }
+
+    #[test]
+    fn can_index_frequencies() {
+        let index: FrequencyIndex = vec![4, 3, 5, 3, 9, 3].into_iter().collect();
+        assert_eq!(3, index.count(3));
+        assert_eq!(0, index.count(2));
+        for number in [3, 4, 2, 1] {
+            assert_eq!(
+                similarity_score(number, &[4, 3, 5, 3, 9, 3]) as i64,
+                index.similarity(number)
+            );
+        }
+    }
+
+    #[test]
+    fn can_calculate_total_similarity() {
+        let left = vec![3, 4, 2, 1, 3, 3];
+        let right = vec![4, 3, 5, 3, 9, 3];
+        assert_eq!(31, total_similarity(left, right));
+        assert_eq!(0, total_similarity(vec![1, 2], vec![]));
+    }
+
+    #[test]
+    fn can_parse_location_lists() {
+        let lists = Dec01::parse("3   4\n4   3\n").unwrap();
+        assert_eq!(vec![3, 4], lists.left);
+        assert_eq!(vec![4, 3], lists.right);
+        assert_eq!(
+            Err(ParseError::missing(2, 2, "2 numbers")),
+            Dec01::parse("3   4\n4\n").map(|_| ())
+        );
+    }
 }
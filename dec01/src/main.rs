@@ -1,32 +1,24 @@
 use std::fs;
 
-use dec01::{difference_vector, similarity_score, sum_up_vector};
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec01::Dec01;
 
-fn main() {
-    let mut left: Vec<i32> = Vec::new();
-    let mut right: Vec<i32> = Vec::new();
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
+fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    for line in input.lines() {
-        let numbers: Vec<i32> = line
-            .split("   ")
-            .map(|n| n.parse().expect("Not a valid integer!"))
-            .collect();
+    let parsed = stats.measure("parse", || Dec01::parse(&input).or_exit("input.txt"));
 
-        left.push(numbers[0]);
-        right.push(numbers[1]);
-    }
+    let answer = stats.measure("part 1", || Dec01::part1(&parsed));
+    println!("part 1: {}", answer);
 
-    // part 1
-    let diff_vector = difference_vector(&left, &right);
-    let diff_sum = sum_up_vector(&diff_vector);
-    println!("part 1: {:?}", diff_sum);
-
-    // part 2
-    let mut similarity_vector: Vec<i32> = Vec::new();
-    for number in &left {
-        similarity_vector.push(similarity_score(*number, &right))
+    let answer = stats.measure("part 2", || Dec01::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("part 2: {}", answer);
     }
-    let similarity_sum = sum_up_vector(&similarity_vector);
-    println!("part 2: {:?}", similarity_sum)
+
+    stats.report();
 }
@@ -1,26 +1,211 @@
-pub fn is_report_safe(report: &[i32]) -> bool {
-    let direction = get_level_direction(report[0], report[1]);
-    for i in 0..(report.len() - 1) {
-        if !are_compatible(report[i], report[i + 1], &direction) {
-            return false;
+use std::fmt;
+
+use aoc_core::{parse_numbers, ParseError, Solution};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Directions {
+    Ascending,
+    Descending,
+    // either one, but the whole report has to stick to it
+    Both,
+}
+
+// what a report has to look like to be safe. steps are the absolute difference between two
+// neighbouring levels, plateaus (a step of 0) don't count as a direction.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SafetyPolicy {
+    pub min_step: u32,
+    pub max_step: u32,
+    pub directions: Directions,
+    pub allow_plateaus: bool,
+}
+
+// the rules of the puzzle: always up or always down, by 1 to 3 at a time
+impl Default for SafetyPolicy {
+    fn default() -> SafetyPolicy {
+        SafetyPolicy {
+            min_step: 1,
+            max_step: 3,
+            directions: Directions::Both,
+            allow_plateaus: false,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Reason {
+    Plateau,
+    StepTooSmall(u32),
+    StepTooLarge(u32),
+    // goes the other way than the levels before it
+    DirectionChanged,
+    DirectionNotAllowed,
+}
+
+// `index` is the level that broke the rule, compared to the one before it
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Violation {
+    pub index: usize,
+    pub reason: Reason,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "level {}: ", self.index)?;
+        match self.reason {
+            Reason::Plateau => write!(f, "same as the level before"),
+            Reason::StepTooSmall(step) => write!(f, "step of {} is too small", step),
+            Reason::StepTooLarge(step) => write!(f, "step of {} is too large", step),
+            Reason::DirectionChanged => write!(f, "changes direction"),
+            Reason::DirectionNotAllowed => write!(f, "goes in a direction that is not allowed"),
+        }
+    }
+}
+
+impl SafetyPolicy {
+    // the first level that breaks a rule, reports with less than two levels are always safe
+    pub fn check(&self, report: &[i32]) -> Result<(), Violation> {
+        let mut direction = LevelDirectionKind::Invalid;
+        for (i, pair) in report.windows(2).enumerate() {
+            match self.check_step(pair[0], pair[1], &direction) {
+                Ok(LevelDirectionKind::Invalid) => (),
+                Ok(step_direction) => direction = step_direction,
+                Err(reason) => {
+                    return Err(Violation {
+                        index: i + 1,
+                        reason,
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_safe(&self, report: &[i32]) -> bool {
+        self.check(report).is_ok()
+    }
+
+    // `direction` is where the report went so far, `Invalid` if that's not decided yet
+    fn check_step(
+        &self,
+        left: i32,
+        right: i32,
+        direction: &LevelDirectionKind,
+    ) -> Result<LevelDirectionKind, Reason> {
+        let step_direction = get_level_direction(left, right);
+        // any two i32 are at most u32::MAX apart
+        let step = left.abs_diff(right);
+        match step_direction {
+            LevelDirectionKind::Invalid if self.allow_plateaus => return Ok(step_direction),
+            LevelDirectionKind::Invalid => return Err(Reason::Plateau),
+            LevelDirectionKind::Ascending if self.directions == Directions::Descending => {
+                return Err(Reason::DirectionNotAllowed)
+            }
+            LevelDirectionKind::Descending if self.directions == Directions::Ascending => {
+                return Err(Reason::DirectionNotAllowed)
+            }
+            _ if *direction != LevelDirectionKind::Invalid && *direction != step_direction => {
+                return Err(Reason::DirectionChanged)
+            }
+            _ => (),
+        }
+
+        if step < self.min_step {
+            Err(Reason::StepTooSmall(step))
+        } else if step > self.max_step {
+            Err(Reason::StepTooLarge(step))
+        } else {
+            Ok(step_direction)
         }
     }
+}
+
+pub fn is_report_safe(report: &[i32]) -> bool {
+    SafetyPolicy::default().is_safe(report)
+}
 
-    true
+// a level that is kept, how many were removed before it and the level and direction that
+// were kept just before it.
+#[derive(Clone, Copy)]
+struct Kept {
+    removals: usize,
+    previous: Option<(usize, usize)>,
 }
 
-fn are_compatible(left: i32, right: i32, direction: &LevelDirectionKind) -> bool {
-    let difference = match direction {
-        LevelDirectionKind::Ascending => right - left,
-        LevelDirectionKind::Descending => left - right,
-        LevelDirectionKind::Invalid => return false,
-    };
+// safe, or safe once any single level is removed
+pub fn is_report_safe_dampened(report: &[i32]) -> bool {
+    is_safe_with_removals(report, &SafetyPolicy::default(), 1).is_some()
+}
+
+// the fewest levels (at most `k`) that have to be removed to make the report safe, `None` if
+// that takes more than `k`. goes through the report once and remembers, for every level that
+// is kept and every direction the report took so far, how few removals it took to get there.
+// what comes after only depends on that last kept level, so that's O(n * k²).
+pub fn is_safe_with_removals(
+    report: &[i32],
+    policy: &SafetyPolicy,
+    k: usize,
+) -> Option<Vec<usize>> {
+    if report.len() <= 1 {
+        return Some(Vec::new());
+    }
+    // per level and direction
+    let mut best: Vec<[Option<Kept>; 3]> = vec![[None; 3]; report.len()];
+    for (first, state) in best.iter_mut().enumerate().take(k + 1) {
+        state[LevelDirectionKind::Invalid.index()] = Some(Kept {
+            removals: first,
+            previous: None,
+        });
+    }
+
+    for last in 0..report.len() {
+        for direction in LevelDirectionKind::ALL {
+            let Some(Kept { removals, .. }) = best[last][direction.index()] else {
+                continue;
+            };
+            let furthest = (last + k + 1 - removals).min(report.len() - 1);
+            for next in last + 1..=furthest {
+                let Ok(step_direction) = policy.check_step(report[last], report[next], &direction)
+                else {
+                    continue;
+                };
+                let next_direction = match step_direction {
+                    LevelDirectionKind::Invalid => direction,
+                    step_direction => step_direction,
+                };
+                let next_removals = removals + next - last - 1;
+                let state = &mut best[next][next_direction.index()];
+                if state.is_none_or(|known| next_removals < known.removals) {
+                    *state = Some(Kept {
+                        removals: next_removals,
+                        previous: Some((last, direction.index())),
+                    });
+                }
+            }
+        }
+    }
+
+    // the cheapest way to end the report, dropping whatever comes after the last kept level
+    let (_, mut last, mut direction) = (0..report.len())
+        .flat_map(|last| (0..3).map(move |direction| (last, direction)))
+        .filter_map(|(last, direction)| {
+            let total = best[last][direction]?.removals + report.len() - 1 - last;
+            (total <= k).then_some((total, last, direction))
+        })
+        .min()?;
 
-    match difference {
-        d if d <= 0 => false,
-        d if d > 3 => false,
-        _ => true,
+    let mut kept = vec![false; report.len()];
+    loop {
+        kept[last] = true;
+        match best[last][direction].and_then(|kept| kept.previous) {
+            Some((previous, previous_direction)) => {
+                last = previous;
+                direction = previous_direction;
+            }
+            None => break,
+        }
     }
+    Some((0..report.len()).filter(|&i| !kept[i]).collect())
 }
 
 fn get_level_direction(left: i32, right: i32) -> LevelDirectionKind {
@@ -31,13 +216,59 @@ fn get_level_direction(left: i32, right: i32) -> LevelDirectionKind {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 enum LevelDirectionKind {
     Ascending,
     Descending,
     Invalid,
 }
 
+impl LevelDirectionKind {
+    const ALL: [LevelDirectionKind; 3] = [
+        LevelDirectionKind::Ascending,
+        LevelDirectionKind::Descending,
+        LevelDirectionKind::Invalid,
+    ];
+
+    fn index(&self) -> usize {
+        match self {
+            LevelDirectionKind::Ascending => 0,
+            LevelDirectionKind::Descending => 1,
+            LevelDirectionKind::Invalid => 2,
+        }
+    }
+}
+
+pub struct Dec02;
+
+impl Solution for Dec02 {
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_numbers(line, i + 1))
+            .collect()
+    }
+
+    fn part1(reports: &Vec<Vec<i32>>) -> String {
+        reports
+            .iter()
+            .filter(|&report| is_report_safe(report))
+            .count()
+            .to_string()
+    }
+
+    fn part2(reports: &Vec<Vec<i32>>) -> Option<String> {
+        let count = reports
+            .iter()
+            .filter(|&report| is_report_safe_dampened(report))
+            .count();
+        Some(count.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,20 +293,257 @@ mod tests {
         assert_eq!(LevelDirectionKind::Ascending, get_level_direction(2, 5));
     }
 
+    #[test]
+    fn can_check_extreme_levels() {
+        assert!(!is_report_safe(&[i32::MIN, i32::MAX]));
+        assert_eq!(
+            Err(Violation {
+                index: 1,
+                reason: Reason::StepTooLarge(u32::MAX)
+            }),
+            SafetyPolicy::default().check(&[i32::MAX, i32::MIN])
+        );
+        assert!(is_report_safe(&[i32::MAX - 2, i32::MAX]));
+        assert_eq!(
+            Some(vec![0]),
+            is_safe_with_removals(&[i32::MIN, 1, 2], &SafetyPolicy::default(), 1)
+        );
+    }
+
     #[test]
     fn can_check_if_levels_are_compatible() {
+        let policy = SafetyPolicy::default();
+        let ascending = LevelDirectionKind::Ascending;
+        let descending = LevelDirectionKind::Descending;
         // equal
-        assert!(!are_compatible(5, 5, &LevelDirectionKind::Ascending));
-        assert!(!are_compatible(5, 5, &LevelDirectionKind::Descending));
+        assert_eq!(Err(Reason::Plateau), policy.check_step(5, 5, &ascending));
+        assert_eq!(Err(Reason::Plateau), policy.check_step(5, 5, &descending));
         // ascending
-        assert!(are_compatible(5, 8, &LevelDirectionKind::Ascending));
-        assert!(!are_compatible(5, 9, &LevelDirectionKind::Ascending));
-        assert!(!are_compatible(5, 8, &LevelDirectionKind::Descending));
-        assert!(!are_compatible(5, 9, &LevelDirectionKind::Descending));
+        assert_eq!(
+            Ok(LevelDirectionKind::Ascending),
+            policy.check_step(5, 8, &ascending)
+        );
+        assert_eq!(
+            Err(Reason::StepTooLarge(4)),
+            policy.check_step(5, 9, &ascending)
+        );
+        assert_eq!(
+            Err(Reason::DirectionChanged),
+            policy.check_step(5, 8, &descending)
+        );
+        assert_eq!(
+            Err(Reason::DirectionChanged),
+            policy.check_step(5, 9, &descending)
+        );
         // descending
-        assert!(are_compatible(5, 2, &LevelDirectionKind::Descending));
-        assert!(!are_compatible(5, 1, &LevelDirectionKind::Descending));
-        assert!(!are_compatible(5, 2, &LevelDirectionKind::Ascending));
-        assert!(!are_compatible(5, 1, &LevelDirectionKind::Ascending));
+        assert_eq!(
+            Ok(LevelDirectionKind::Descending),
+            policy.check_step(5, 2, &descending)
+        );
+        assert_eq!(
+            Err(Reason::StepTooLarge(4)),
+            policy.check_step(5, 1, &descending)
+        );
+        assert_eq!(
+            Err(Reason::DirectionChanged),
+            policy.check_step(5, 2, &ascending)
+        );
+        assert_eq!(
+            Err(Reason::DirectionChanged),
+            policy.check_step(5, 1, &ascending)
+        );
+    }
+
+    #[test]
+    fn can_find_first_violation() {
+        let policy = SafetyPolicy::default();
+        assert_eq!(Ok(()), policy.check(&[7, 6, 4, 2, 1]));
+        assert_eq!(Ok(()), policy.check(&[7]));
+        let violation = policy.check(&[1, 2, 7, 8, 9]).unwrap_err();
+        assert_eq!(
+            Violation {
+                index: 2,
+                reason: Reason::StepTooLarge(5)
+            },
+            violation
+        );
+        assert_eq!("level 2: step of 5 is too large", violation.to_string());
+        assert_eq!(
+            Err(Violation {
+                index: 2,
+                reason: Reason::DirectionChanged
+            }),
+            policy.check(&[1, 3, 2, 4, 5])
+        );
+        assert_eq!(
+            Err(Violation {
+                index: 3,
+                reason: Reason::Plateau
+            }),
+            policy.check(&[8, 6, 4, 4, 1])
+        );
+    }
+
+    #[test]
+    fn can_check_with_custom_policy() {
+        let policy = SafetyPolicy {
+            min_step: 2,
+            max_step: 5,
+            directions: Directions::Ascending,
+            allow_plateaus: true,
+        };
+        assert!(policy.is_safe(&[1, 1, 6, 8, 8, 10]));
+        assert_eq!(
+            Err(Violation {
+                index: 2,
+                reason: Reason::StepTooSmall(1)
+            }),
+            policy.check(&[1, 3, 4])
+        );
+        assert_eq!(
+            Err(Violation {
+                index: 1,
+                reason: Reason::DirectionNotAllowed
+            }),
+            policy.check(&[9, 7, 5])
+        );
+
+        // the direction is decided by the first step that is not a plateau
+        let policy = SafetyPolicy {
+            allow_plateaus: true,
+            ..SafetyPolicy::default()
+        };
+        assert!(policy.is_safe(&[4, 4, 3, 3, 1]));
+        assert_eq!(
+            Err(Violation {
+                index: 4,
+                reason: Reason::DirectionChanged
+            }),
+            policy.check(&[4, 4, 3, 3, 4])
+        );
+    }
+
+    #[test]
+    fn can_test_for_safety_with_dampener() {
+        assert!(is_report_safe_dampened(&[7, 6, 4, 2, 1]));
+        assert!(!is_report_safe_dampened(&[1, 2, 7, 8, 9]));
+        assert!(!is_report_safe_dampened(&[9, 7, 6, 2, 1]));
+        assert!(is_report_safe_dampened(&[1, 3, 2, 4, 5]));
+        assert!(is_report_safe_dampened(&[8, 6, 4, 4, 1]));
+        assert!(is_report_safe_dampened(&[1, 3, 6, 7, 9]));
+    }
+
+    #[test]
+    fn can_find_levels_to_remove() {
+        let policy = SafetyPolicy::default();
+        assert_eq!(
+            Some(vec![]),
+            is_safe_with_removals(&[7, 6, 4, 2, 1], &policy, 1)
+        );
+        assert_eq!(
+            Some(vec![1]),
+            is_safe_with_removals(&[1, 5, 2, 3], &policy, 1)
+        );
+        // either one of the 4s
+        let removed = is_safe_with_removals(&[8, 6, 4, 4, 1], &policy, 1).unwrap();
+        assert!(removed == vec![2] || removed == vec![3]);
+        assert_eq!(None, is_safe_with_removals(&[1, 2, 7, 8, 9], &policy, 1));
+        assert_eq!(
+            Some(vec![0]),
+            is_safe_with_removals(&[9, 1, 2, 3], &policy, 1)
+        );
+        assert_eq!(
+            Some(vec![3]),
+            is_safe_with_removals(&[1, 2, 3, 9], &policy, 1)
+        );
+        assert_eq!(
+            Some(vec![1, 2]),
+            is_safe_with_removals(&[1, 9, 0, 2, 3], &policy, 2)
+        );
+        assert_eq!(None, is_safe_with_removals(&[1, 9, 0, 2, 3], &policy, 1));
+        assert_eq!(
+            Some(vec![1]),
+            is_safe_with_removals(
+                &[1, 9, 2],
+                &SafetyPolicy {
+                    max_step: 1,
+                    ..SafetyPolicy::default()
+                },
+                1
+            )
+        );
+    }
+
+    #[test]
+    fn can_remove_levels_like_brute_force() {
+        // every subset of at most k removed levels, the fewest that work
+        fn brute_force(report: &[i32], policy: &SafetyPolicy, k: usize) -> Option<usize> {
+            (0u32..1 << report.len())
+                .filter(|removed| removed.count_ones() as usize <= k)
+                .filter(|removed| {
+                    let kept: Vec<i32> = (0..report.len())
+                        .filter(|i| removed & (1 << i) == 0)
+                        .map(|i| report[i])
+                        .collect();
+                    policy.is_safe(&kept)
+                })
+                .map(|removed| removed.count_ones() as usize)
+                .min()
+        }
+
+        let policies = [
+            SafetyPolicy::default(),
+            SafetyPolicy {
+                allow_plateaus: true,
+                ..SafetyPolicy::default()
+            },
+        ];
+        let mut seed: u64 = 42;
+        for _ in 0..500 {
+            let report: Vec<i32> = (0..7)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    (seed % 8) as i32
+                })
+                .collect();
+            for policy in &policies {
+                for k in 0..3 {
+                    let removed = is_safe_with_removals(&report, policy, k);
+                    assert_eq!(
+                        brute_force(&report, policy, k),
+                        removed.as_ref().map(|removed| removed.len()),
+                        "{:?} with k = {}",
+                        report,
+                        k
+                    );
+                    if let Some(removed) = removed {
+                        let kept: Vec<i32> = (0..report.len())
+                            .filter(|i| !removed.contains(i))
+                            .map(|i| report[i])
+                            .collect();
+                        assert!(policy.is_safe(&kept));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn can_parse_reports() {
+        assert_eq!(
+            Ok(vec![vec![7, 6, 4], vec![1, 2]]),
+            Dec02::parse("7 6 4\n1 2\n")
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(2, 3, "x", "a number")),
+            Dec02::parse("7 6 4\n1 x\n")
+        );
+        // an empty report would count as safe
+        assert_eq!(
+            Err(ParseError::missing(2, 1, "a number")),
+            Dec02::parse("1 2 3\n\n4 5 6\n")
+        );
     }
 }
@@ -1,44 +1,24 @@
 use std::fs;
 
-use dec02::is_report_safe;
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec02::Dec02;
 
-fn main() {
-    let mut reports: Vec<Vec<i32>> = Vec::new();
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
+fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
+    let parsed = stats.measure("parse", || Dec02::parse(&input).or_exit("input.txt"));
 
-    for line in input.lines() {
-        let levels: Vec<i32> = line
-            .split(" ")
-            .map(|n| n.parse().expect("Not a valid integer!"))
-            .collect();
-        reports.push(levels);
-    }
-
-    let safe_report_count = reports
-        .iter()
-        .filter(|&report| is_report_safe(report))
-        .count();
-
-    println!("part1: {:?}", safe_report_count);
+    let answer = stats.measure("part 1", || Dec02::part1(&parsed));
+    println!("part1: {}", answer);
 
-    let safe_report_dampened_count = reports
-        .iter()
-        .filter(|&report| {
-            if is_report_safe(report) {
-                true
-            } else {
-                for i in 0..report.len() {
-                    let mut dampened_report = report.clone();
-                    dampened_report.remove(i);
-                    if is_report_safe(&dampened_report) {
-                        return true;
-                    }
-                }
-                false
-            }
-        })
-        .count();
+    let answer = stats.measure("part 2", || Dec02::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("part2: {}", answer);
+    }
 
-    println!("part2: {:?}", safe_report_dampened_count);
+    stats.report();
 }
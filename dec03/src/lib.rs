@@ -1,67 +1,576 @@
-use regex::Regex;
+use std::fmt;
+use std::ops::Range;
 
-pub fn apply_conditionals(haystack: &str) -> String {
-    let mut proper_haystack = haystack.to_string();
+use aoc_core::{column_at, ParseError, Solution};
 
-    let re = Regex::new(r"don't\(\).*?do\(\)").unwrap();
-    let captures: Vec<regex::Match> = re.find_iter(haystack).collect();
-    for m in captures.into_iter().rev() {
-        proper_haystack.replace_range(m.start()..m.end(), "");
+// the arguments always have as many numbers as the opcode's arity. `None` if the result can't
+// be computed, e.g. because it doesn't fit into 64 bits or for a division by zero.
+type Evaluate = Box<dyn Fn(&[i64]) -> Option<i64>>;
+
+pub struct Opcode {
+    pub name: String,
+    pub arity: usize,
+    evaluate: Evaluate,
+}
+
+// the instructions the lexer looks for, besides `do()` and `don't()`. an instruction is the
+// name followed by its arguments in parentheses, e.g. `mul(2,4)`.
+pub struct InstructionSet {
+    opcodes: Vec<Opcode>,
+}
+
+impl InstructionSet {
+    // no instructions at all, `default` has `mul`
+    pub fn empty() -> InstructionSet {
+        InstructionSet {
+            opcodes: Vec::new(),
+        }
+    }
+
+    // returns the opcode the instruction is known by
+    pub fn register(
+        &mut self,
+        name: &str,
+        arity: usize,
+        evaluate: impl Fn(&[i64]) -> Option<i64> + 'static,
+    ) -> usize {
+        self.opcodes.push(Opcode {
+            name: name.to_string(),
+            arity,
+            evaluate: Box::new(evaluate),
+        });
+        self.opcodes.len() - 1
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.opcodes.iter().position(|opcode| opcode.name == name)
     }
 
-    proper_haystack
+    pub fn opcode(&self, opcode: usize) -> &Opcode {
+        &self.opcodes[opcode]
+    }
+
+    pub fn evaluate(&self, opcode: usize, args: &[i64]) -> Option<i64> {
+        (self.opcodes[opcode].evaluate)(args)
+    }
+}
+
+// just `mul`, like in the puzzle
+impl Default for InstructionSet {
+    fn default() -> InstructionSet {
+        let mut instructions = InstructionSet::empty();
+        instructions.register("mul", 2, |args| args[0].checked_mul(args[1]));
+        instructions
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Token {
+    Call { opcode: usize, args: Vec<i64> },
+    Do,
+    Dont,
 }
 
-pub fn detect_multiplications(haystack: &str) -> Vec<String> {
-    // let mut mul
-    let re = Regex::new(r"mul\(\d+,\d+\)").unwrap();
-    let captures: Vec<String> = re
-        .find_iter(haystack)
-        .map(|mat| mat.as_str().to_string())
-        .collect();
-    captures
+// a token and the bytes of the memory it was read from
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Spanned {
+    pub token: Token,
+    pub span: Range<usize>,
 }
 
-pub fn multiply(instruction: &str) -> i32 {
-    let re = Regex::new(r"mul\((?P<left>\d+),(?P<right>\d+)\)").unwrap();
-    let numbers = re.captures(instruction).unwrap();
-    let left: i32 = numbers["left"].parse().unwrap();
-    let right: i32 = numbers["right"].parse().unwrap();
-    left * right
+// reads the instructions out of corrupted memory one at a time, everything in between is
+// skipped. line breaks are just more garbage.
+pub struct Lexer<'a> {
+    memory: &'a [u8],
+    instructions: &'a InstructionSet,
+    position: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(memory: &'a str, instructions: &'a InstructionSet) -> Lexer<'a> {
+        Lexer {
+            memory: memory.as_bytes(),
+            instructions,
+            position: 0,
+        }
+    }
+
+    // the token that starts right at `start` and where it ends
+    fn token_at(&self, start: usize) -> Option<(Token, usize)> {
+        let rest = &self.memory[start..];
+        if rest.starts_with(b"do()") {
+            return Some((Token::Do, start + 4));
+        }
+        if rest.starts_with(b"don't()") {
+            return Some((Token::Dont, start + 7));
+        }
+        self.instructions
+            .opcodes
+            .iter()
+            .enumerate()
+            .filter(|(_, opcode)| rest.starts_with(opcode.name.as_bytes()))
+            .find_map(|(i, opcode)| {
+                let mut position = start + opcode.name.len();
+                let args = self.args_at(&mut position, opcode.arity)?;
+                Some((Token::Call { opcode: i, args }, position))
+            })
+    }
+
+    // `(1,2,3)`, with exactly `arity` numbers
+    fn args_at(&self, position: &mut usize, arity: usize) -> Option<Vec<i64>> {
+        self.expect(position, b'(')?;
+        let mut args = Vec::with_capacity(arity);
+        for i in 0..arity {
+            if i > 0 {
+                self.expect(position, b',')?;
+            }
+            args.push(self.number_at(position)?);
+        }
+        self.expect(position, b')')?;
+        Some(args)
+    }
+
+    fn number_at(&self, position: &mut usize) -> Option<i64> {
+        let digits = self.memory[*position..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        let number = std::str::from_utf8(&self.memory[*position..*position + digits])
+            .ok()?
+            .parse()
+            .ok()?;
+        *position += digits;
+        Some(number)
+    }
+
+    fn expect(&self, position: &mut usize, byte: u8) -> Option<()> {
+        (self.memory.get(*position) == Some(&byte)).then(|| *position += 1)
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Spanned;
+
+    fn next(&mut self) -> Option<Spanned> {
+        while self.position < self.memory.len() {
+            let start = self.position;
+            if let Some((token, end)) = self.token_at(start) {
+                self.position = end;
+                return Some(Spanned {
+                    token,
+                    span: start..end,
+                });
+            }
+            self.position += 1;
+        }
+        None
+    }
+}
+
+// an instruction that could not be evaluated, or that pushed the total past 64 bits
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct EvaluationError {
+    pub span: Range<usize>,
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the instruction at bytes {}..{} can't be evaluated with 64 bits",
+            self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for EvaluationError {}
+
+impl EvaluationError {
+    // the same error with a line and column, for reporting it like bad input
+    pub fn locate(&self, memory: &str) -> ParseError {
+        let line_start = memory[..self.span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line = memory[..line_start].matches('\n').count() + 1;
+        ParseError::unexpected(
+            line,
+            column_at(&memory[line_start..], self.span.start - line_start),
+            &memory[self.span.clone()],
+            "an instruction that can be evaluated with 64 bits",
+        )
+    }
+}
+
+// keeps track of whether instructions are enabled. without conditionals `do()` and `don't()`
+// are ignored and everything counts.
+pub struct Interpreter<'a> {
+    instructions: &'a InstructionSet,
+    conditionals: bool,
+    enabled: bool,
+    total: i64,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(instructions: &'a InstructionSet, conditionals: bool) -> Interpreter<'a> {
+        Interpreter {
+            instructions,
+            conditionals,
+            enabled: true,
+            total: 0,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    // what the token added to the total
+    pub fn step(&mut self, spanned: &Spanned) -> Result<i64, EvaluationError> {
+        let error = || EvaluationError {
+            span: spanned.span.clone(),
+        };
+        let contribution = match &spanned.token {
+            Token::Call { opcode, args } if self.enabled => self
+                .instructions
+                .evaluate(*opcode, args)
+                .ok_or_else(error)?,
+            Token::Call { .. } => 0,
+            Token::Do => {
+                self.enabled = true;
+                0
+            }
+            Token::Dont => {
+                self.enabled = !self.conditionals;
+                0
+            }
+        };
+        self.total = self.total.checked_add(contribution).ok_or_else(error)?;
+        Ok(contribution)
+    }
+}
+
+pub fn execute(
+    memory: &str,
+    instructions: &InstructionSet,
+    conditionals: bool,
+) -> Result<i64, EvaluationError> {
+    let mut interpreter = Interpreter::new(instructions, conditionals);
+    for spanned in Lexer::new(memory, instructions) {
+        interpreter.step(&spanned)?;
+    }
+    Ok(interpreter.total())
+}
+
+// one token as the interpreter saw it. `enabled` is whether instructions are enabled once the
+// token is done, for a call that's whether it counted.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TraceEntry {
+    pub token: Token,
+    pub span: Range<usize>,
+    pub enabled: bool,
+    pub contribution: i64,
+}
+
+pub fn trace(
+    memory: &str,
+    instructions: &InstructionSet,
+    conditionals: bool,
+) -> Result<Vec<TraceEntry>, EvaluationError> {
+    let mut interpreter = Interpreter::new(instructions, conditionals);
+    Lexer::new(memory, instructions)
+        .map(|spanned| {
+            let contribution = interpreter.step(&spanned)?;
+            Ok(TraceEntry {
+                token: spanned.token,
+                span: spanned.span,
+                enabled: interpreter.enabled(),
+                contribution,
+            })
+        })
+        .collect()
+}
+
+// one line per entry: the span, the instruction as it's written in memory, on or off and what
+// it added to the total.
+pub fn format_trace(memory: &str, entries: &[TraceEntry]) -> String {
+    let mut output = String::new();
+    for entry in entries {
+        let span = format!("{}..{}", entry.span.start, entry.span.end);
+        output.push_str(&format!(
+            "{:>15}  {:<24} {:<3} {:>12}\n",
+            span,
+            &memory[entry.span.clone()],
+            if entry.enabled { "on" } else { "off" },
+            entry.contribution
+        ));
+    }
+    output
+}
+
+const ACTIVE: &str = "\x1b[1;32m";
+const SKIPPED: &str = "\x1b[31m";
+const SWITCH: &str = "\x1b[1;33m";
+const INACTIVE: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+// the memory with terminal colors: instructions that counted are green, the ones that were
+// skipped red, `do()` and `don't()` yellow. everything while instructions are off is dimmed.
+pub fn highlight(memory: &str, entries: &[TraceEntry]) -> String {
+    let mut output = String::new();
+    let mut position = 0;
+    let mut enabled = true;
+    for entry in entries {
+        push_region(&mut output, &memory[position..entry.span.start], enabled);
+        let color = match entry.token {
+            Token::Call { .. } if entry.enabled => ACTIVE,
+            Token::Call { .. } => SKIPPED,
+            Token::Do | Token::Dont => SWITCH,
+        };
+        output.push_str(color);
+        output.push_str(&memory[entry.span.clone()]);
+        output.push_str(RESET);
+        position = entry.span.end;
+        enabled = entry.enabled;
+    }
+    push_region(&mut output, &memory[position..], enabled);
+    output
+}
+
+fn push_region(output: &mut String, text: &str, enabled: bool) {
+    if enabled || text.is_empty() {
+        output.push_str(text);
+    } else {
+        output.push_str(INACTIVE);
+        output.push_str(text);
+        output.push_str(RESET);
+    }
+}
+
+pub struct Dec03;
+
+// the totals with and without conditionals. they're worked out while parsing, so an
+// instruction that can't be evaluated is reported like any other bad input.
+pub struct Totals {
+    pub unconditional: i64,
+    pub conditional: i64,
+}
+
+impl Solution for Dec03 {
+    // a `don't()` holds until the next `do()`, across lines
+    type Input = Totals;
+
+    fn parse(input: &str) -> Result<Totals, ParseError> {
+        let instructions = InstructionSet::default();
+        let total = |conditionals| {
+            execute(input, &instructions, conditionals).map_err(|error| error.locate(input))
+        };
+        Ok(Totals {
+            unconditional: total(false)?,
+            conditional: total(true)?,
+        })
+    }
+
+    fn part1(totals: &Totals) -> String {
+        totals.unconditional.to_string()
+    }
+
+    fn part2(totals: &Totals) -> Option<String> {
+        Some(totals.conditional.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn tokens(memory: &str, instructions: &InstructionSet) -> Vec<Token> {
+        Lexer::new(memory, instructions)
+            .map(|spanned| spanned.token)
+            .collect()
+    }
+
+    fn mul(left: i64, right: i64) -> Token {
+        Token::Call {
+            opcode: 0,
+            args: vec![left, right],
+        }
+    }
+
+    fn arithmetic() -> InstructionSet {
+        let mut instructions = InstructionSet::default();
+        instructions.register("add", 2, |args| args[0].checked_add(args[1]));
+        instructions.register("sub", 2, |args| args[0].checked_sub(args[1]));
+        instructions.register("div", 2, |args| args[0].checked_div(args[1]));
+        instructions.register("sum", 3, |args| {
+            args.iter().try_fold(0i64, |sum, &n| sum.checked_add(n))
+        });
+        instructions.register("one", 0, |_| Some(1));
+        instructions
+    }
+
     #[test]
-    fn can_apply_conditionals() {
+    fn can_lex_instructions() {
+        let instructions = InstructionSet::default();
         assert_eq!(
-            "xmul(2,4)&mul[3,7]!^?mul(8,5))",
-            apply_conditionals(&String::from(
-                "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
-            ))
+            vec![mul(2, 4), mul(3, 12)],
+            tokens("xmul(2,4)%&mul[3,7]!mul(3,12)", &instructions)
         );
         assert_eq!(
-            String::from("blablablab_1do()2do()3_4567_pepi"),
-            apply_conditionals(&String::from(
-                "blablablab_don't()blablablado()1do()2do()3_4567_don't()hugohugoghuo_do()pepi",
-            ))
+            vec![Token::Dont, Token::Do, mul(1, 2)],
+            tokens(
+                "don't()do()mul(1,2)mul(1,2mul(,2)mul(1 ,2)do(",
+                &instructions
+            )
         );
+        assert_eq!(vec![mul(50, 500)], tokens("mumul(50,500)", &instructions));
+    }
+
+    #[test]
+    fn can_lex_byte_offsets() {
+        let instructions = InstructionSet::default();
+        let spans: Vec<Range<usize>> = Lexer::new("xmul(2,4)\ndon't()_do()", &instructions)
+            .map(|spanned| spanned.span)
+            .collect();
+        assert_eq!(vec![1..9, 10..17, 18..22], spans);
     }
 
     #[test]
-    fn can_detect_multiplications() {
+    fn can_interpret_conditionals() {
+        let instructions = InstructionSet::default();
+        let memory = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(Ok(161), execute(memory, &instructions, false));
+        assert_eq!(Ok(48), execute(memory, &instructions, true));
+        // a `don't()` without a `do()` lasts until the end, across lines
         assert_eq!(
-            vec!["mul(2,4)", "mul(3,12)"],
-            detect_multiplications(&String::from("xmul(2,4)%&mul[3,7]!mul(3,12)"))
-        )
+            Ok(8),
+            execute("mul(2,4)don't()mul(5,5)\nmul(3,3)", &instructions, true)
+        );
+        assert_eq!(
+            Ok(6),
+            execute(
+                "mul(1,1)don't()\nmul(7,7)do()\nmul(1,5)don't()mul(9,9)",
+                &instructions,
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn can_step_through_tokens() {
+        let instructions = InstructionSet::default();
+        let mut interpreter = Interpreter::new(&instructions, true);
+        let mut step = |token: Token| interpreter.step(&Spanned { token, span: 0..1 });
+        assert_eq!(Ok(8), step(mul(2, 4)));
+        assert_eq!(Ok(0), step(Token::Dont));
+        assert_eq!(Ok(0), step(mul(5, 5)));
+        step(Token::Do).unwrap();
+        assert_eq!(Ok(40), step(mul(8, 5)));
+        assert!(interpreter.enabled());
+        assert_eq!(48, interpreter.total());
+    }
+
+    #[test]
+    fn can_register_instructions() {
+        assert_eq!(None, InstructionSet::empty().find("mul"));
+        assert_eq!(Some(0), InstructionSet::default().find("mul"));
+        let instructions = arithmetic();
+        assert_eq!(Some(3), instructions.find("div"));
+        assert_eq!(3, instructions.opcode(4).arity);
+        assert_eq!(
+            vec![
+                Token::Call {
+                    opcode: 1,
+                    args: vec![1, 2]
+                },
+                Token::Call {
+                    opcode: 4,
+                    args: vec![1, 2, 3]
+                },
+                Token::Call {
+                    opcode: 5,
+                    args: vec![]
+                },
+            ],
+            tokens("add(1,2)sum(1,2)sum(1,2,3)one()one(1)", &instructions)
+        );
+        assert_eq!(
+            Ok(8 + 3 - 4 + 2 + 6 + 1),
+            execute(
+                "mul(2,4)add(1,2)sub(1,5)div(7,3)sum(1,2,3)one()",
+                &instructions,
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn can_trace_instructions() {
+        let memory = "xmul(2,4)&don't()_mul(5,5)+undo()?mul(8,5))";
+        let entries = trace(memory, &InstructionSet::default(), true).unwrap();
+        assert_eq!(
+            TraceEntry {
+                token: mul(2, 4),
+                span: 1..9,
+                enabled: true,
+                contribution: 8
+            },
+            entries[0]
+        );
+        assert_eq!(
+            vec![(false, 0), (false, 0), (true, 0), (true, 40)],
+            entries[1..]
+                .iter()
+                .map(|entry| (entry.enabled, entry.contribution))
+                .collect::<Vec<(bool, i64)>>()
+        );
+        assert_eq!(
+            "           1..9  mul(2,4)                 on             8\n         10..17  don't()                  off            0\n",
+            format_trace(memory, &entries[..2])
+        );
+    }
+
+    #[test]
+    fn can_highlight_memory() {
+        let memory = "mul(2,4)don't()_mul(5,5)do()x";
+        let entries = trace(memory, &InstructionSet::default(), true).unwrap();
+        assert_eq!(
+            "\x1b[1;32mmul(2,4)\x1b[0m\x1b[1;33mdon't()\x1b[0m\x1b[2m_\x1b[0m\x1b[31mmul(5,5)\x1b[0m\x1b[1;33mdo()\x1b[0mx",
+            highlight(memory, &entries)
+        );
     }
 
     #[test]
-    fn can_multiply() {
-        assert_eq!(8, multiply(&String::from("mul(2,4)")));
-        assert_eq!(0, multiply(&String::from("mul(2,0)")));
-        assert_eq!(25000, multiply(&String::from("mul(50,500)")));
+    fn can_report_overflows() {
+        let instructions = arithmetic();
+        assert_eq!(
+            Err(EvaluationError { span: 8..16 }),
+            execute("mul(2,4)div(1,0)", &instructions, false)
+        );
+        let memory = "mul(9223372036854775807,1)add(1,0)";
+        assert_eq!(
+            Err(EvaluationError { span: 26..34 }),
+            execute(memory, &instructions, false)
+        );
+        assert_eq!(
+            Err(EvaluationError { span: 0..26 }),
+            execute("mul(4294967296,4294967296)", &instructions, false)
+        );
+        let memory = "mul(2,4)\nxmul(9223372036854775807,2)";
+        assert_eq!(
+            Err(ParseError::unexpected(
+                2,
+                2,
+                "mul(9223372036854775807,2)",
+                "an instruction that can be evaluated with 64 bits"
+            )),
+            Dec03::parse(memory).map(|_| ())
+        );
+        // the old `i32` would have overflowed here
+        assert_eq!(
+            Ok(1_000_000_000_000),
+            execute("mul(1000000,1000000)", &instructions, false)
+        );
     }
 }
@@ -1,20 +1,41 @@
+use std::env;
 use std::fs;
 
-use dec03::{apply_conditionals, detect_multiplications, multiply};
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec03::{format_trace, highlight, trace, Dec03, InstructionSet};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let normalized_input = input.replace('\n', "");
+    let parsed = stats.measure("parse", || Dec03::parse(&input).or_exit("input.txt"));
+
+    let answer = stats.measure("part 1", || Dec03::part1(&parsed));
+    println!("part1: {}", answer);
+
+    let answer = stats.measure("part 2", || Dec03::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("part2: {}", answer);
+    }
 
-    let multiplications = detect_multiplications(&normalized_input);
-    let multiplication_result: i32 = multiplications.iter().map(|m| multiply(m)).sum();
-    println!("part1: {:?}", multiplication_result);
+    // `--trace` lists every instruction of part 2, `--highlight` shows them in the memory
+    let args: Vec<String> = env::args().skip(1).collect();
+    let tracing = args.iter().any(|arg| arg == "--trace");
+    let highlighting = args.iter().any(|arg| arg == "--highlight");
+    if tracing || highlighting {
+        let entries = trace(&input, &InstructionSet::default(), true)
+            .map_err(|error| error.locate(&input))
+            .or_exit("input.txt");
+        if tracing {
+            print!("{}", format_trace(&input, &entries));
+        }
+        if highlighting {
+            print!("{}", highlight(&input, &entries));
+        }
+    }
 
-    let conditional_input = apply_conditionals(&normalized_input);
-    let conditional_multiplications = detect_multiplications(&conditional_input);
-    let multiplication_result: i32 = conditional_multiplications
-        .iter()
-        .map(|m| multiply(m))
-        .sum();
-    println!("part2: {:?}", multiplication_result);
+    stats.report();
 }
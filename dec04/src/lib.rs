@@ -1,176 +1,392 @@
-use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
 
-pub fn extract_lines(grid: &Vec<Vec<char>>) -> Vec<String> {
-    let mut all: Vec<String> = Vec::new();
-    let row_count = grid.len();
-    let column_count = grid[0].len();
+use aoc_core::{fields, ParseError, Solution};
+use aoc_grid::{Direction, Grid, Point};
 
-    let mut horizontal: Vec<String> = Vec::new();
-    for row in grid {
-        horizontal.push(row.iter().collect());
-    }
-    all.extend(horizontal);
-
-    let mut vertical: Vec<String> = Vec::new();
-    for column_index in 0..column_count {
-        let mut string = String::new();
-        for row in grid {
-            string.push(row[column_index]);
-        }
-        vertical.push(string);
-    }
-    all.extend(vertical);
+// a word that was found, `start` is its first letter and `direction` the way it's written
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Match {
+    pub word: usize,
+    pub start: Point,
+    pub direction: Direction,
+}
 
-    let mut diagonal: Vec<String> = Vec::new();
+// a node of the automaton: where to go on the next letter, where to continue when there is no
+// such letter, and the words that end here (including the ones that end in a suffix of it).
+#[derive(Default)]
+struct State {
+    next: HashMap<char, usize>,
+    fail: usize,
+    words: Vec<usize>,
+}
 
-    for start_column in 0..column_count {
-        let mut string = String::new();
-        let mut x = start_column;
-        let mut y = 0;
+// looks for many words at once in all eight directions. every line of the grid in every
+// direction is fed through an Aho-Corasick automaton of the words, so each cell is read once
+// per direction no matter how many words there are.
+pub struct WordSearch {
+    words: Vec<String>,
+    states: Vec<State>,
+}
 
-        while x < column_count && y < row_count {
-            string.push(grid[y][x]);
-            x += 1;
-            y += 1;
+impl WordSearch {
+    pub fn new(words: &[&str]) -> WordSearch {
+        let mut states: Vec<State> = vec![State::default()];
+        for (i, word) in words.iter().enumerate() {
+            let mut current = 0;
+            for letter in word.chars() {
+                current = match states[current].next.get(&letter) {
+                    Some(&next) => next,
+                    None => {
+                        states.push(State::default());
+                        let next = states.len() - 1;
+                        states[current].next.insert(letter, next);
+                        next
+                    }
+                };
+            }
+            if current != 0 {
+                states[current].words.push(i);
+            }
         }
-        diagonal.push(string);
-    }
 
-    for start_row in 1..row_count {
-        let mut string = String::new();
-        let mut x = 0;
-        let mut y = start_row;
+        // breadth first, so the fail link of a state's parent is always done already
+        let mut queue: VecDeque<usize> = states[0].next.values().copied().collect();
+        while let Some(current) = queue.pop_front() {
+            let next: Vec<(char, usize)> = states[current]
+                .next
+                .iter()
+                .map(|(&letter, &next)| (letter, next))
+                .collect();
+            for (letter, next) in next {
+                let mut fail = states[current].fail;
+                while fail != 0 && !states[fail].next.contains_key(&letter) {
+                    fail = states[fail].fail;
+                }
+                let fail = states[fail].next.get(&letter).copied().unwrap_or(0);
+                states[next].fail = fail;
+                let inherited = states[fail].words.clone();
+                states[next].words.extend(inherited);
+                queue.push_back(next);
+            }
+        }
 
-        while x < column_count && y < column_count {
-            string.push(grid[y][x]);
-            x += 1;
-            y += 1;
+        WordSearch {
+            words: words.iter().map(|word| word.to_string()).collect(),
+            states,
         }
-        diagonal.push(string);
     }
 
-    for start_column in 0..column_count {
-        let mut string = String::new();
-        let mut x = start_column;
-        let mut y: i32 = (row_count - 1).try_into().unwrap(); // last iteration will be -1, so we can't have usize
+    pub fn word(&self, index: usize) -> &str {
+        &self.words[index]
+    }
 
-        while x < column_count && y >= 0 {
-            string.push(grid[y as usize][x]);
-            x += 1;
-            y -= 1;
+    fn advance(&self, mut current: usize, letter: char) -> usize {
+        loop {
+            if let Some(&next) = self.states[current].next.get(&letter) {
+                return next;
+            }
+            if current == 0 {
+                return 0;
+            }
+            current = self.states[current].fail;
         }
-        diagonal.push(string);
     }
 
-    for start_row in (0..row_count - 1).rev() {
-        let mut string = String::new();
-        let mut x = 0;
-        let mut y: i32 = start_row.try_into().unwrap();
-
-        while x < column_count && y >= 0 {
-            string.push(grid[y as usize][x]); // last iteration will be -1, so we can't have usize
-            x += 1;
-            y -= 1;
+    // in the order of the directions in `Direction::ALL`, then line by line. a one letter word
+    // reads the same in every direction, it's only reported once, going `Right`.
+    pub fn find(&self, grid: &Grid<char>) -> Vec<Match> {
+        let mut matches: Vec<Match> = Vec::new();
+        for direction in Direction::ALL {
+            for line in grid.lines(direction) {
+                let mut current = 0;
+                for (point, &letter) in line {
+                    current = self.advance(current, letter);
+                    for &word in &self.states[current].words {
+                        let length = self.words[word].chars().count() as i32;
+                        if length == 1 && direction != Direction::Right {
+                            continue;
+                        }
+                        matches.push(Match {
+                            word,
+                            start: point.steps(direction.opposite(), length - 1),
+                            direction,
+                        });
+                    }
+                }
+            }
         }
-        diagonal.push(string);
+        matches
     }
 
-    all.extend(diagonal);
+    pub fn count(&self, grid: &Grid<char>) -> usize {
+        self.find(grid).len()
+    }
+}
+
+// a 2d pattern like `M.S/.A./M.S`, rows are separated by `/` and `.` matches anything
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Stencil {
+    rows: Vec<Vec<Option<char>>>,
+}
 
-    all
+// where a stencil matched, `variant` is the index in `Stencil::variants`
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Placement {
+    pub top_left: Point,
+    pub variant: usize,
 }
 
-pub fn count_xmas(line: &str) -> i32 {
-    if line.len() < 4 {
-        return 0;
+impl Stencil {
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    // a quarter turn clockwise
+    pub fn rotate(&self) -> Stencil {
+        let rows = (0..self.width())
+            .map(|x| (0..self.height()).rev().map(|y| self.rows[y][x]).collect())
+            .collect();
+        Stencil { rows }
+    }
+
+    // mirrored left to right
+    pub fn reflect(&self) -> Stencil {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row.iter().rev().copied().collect())
+            .collect();
+        Stencil { rows }
     }
 
-    let re = Regex::new(r"XMAS").unwrap();
-    let forward_count = re.captures_iter(line).count();
+    // all rotations and reflections, the ones that look the same are only in here once
+    pub fn variants(&self) -> Vec<Stencil> {
+        let mut variants: Vec<Stencil> = Vec::new();
+        let mut current = self.clone();
+        for _ in 0..4 {
+            for variant in [current.clone(), current.reflect()] {
+                if !variants.contains(&variant) {
+                    variants.push(variant);
+                }
+            }
+            current = current.rotate();
+        }
+        variants
+    }
 
-    let rev_line: String = line.chars().rev().collect();
-    let reverse_count = re.captures_iter(&rev_line).count();
+    pub fn matches_at(&self, grid: &Grid<char>, top_left: Point) -> bool {
+        self.rows.iter().enumerate().all(|(y, row)| {
+            row.iter().enumerate().all(|(x, cell)| {
+                let point = Point::new(top_left.x + x as i32, top_left.y + y as i32);
+                match (cell, grid.get(point)) {
+                    (_, None) => false,
+                    (None, Some(_)) => true,
+                    (Some(expected), Some(found)) => expected == found,
+                }
+            })
+        })
+    }
 
-    (forward_count + reverse_count).try_into().unwrap()
+    // every place any of the variants fits, variant by variant and row by row
+    pub fn find(&self, grid: &Grid<char>) -> Vec<Placement> {
+        let mut placements: Vec<Placement> = Vec::new();
+        for (variant, stencil) in self.variants().iter().enumerate() {
+            if stencil.width() > grid.width() || stencil.height() > grid.height() {
+                continue;
+            }
+            for y in 0..=grid.height() - stencil.height() {
+                for x in 0..=grid.width() - stencil.width() {
+                    let top_left = Point::new(x as i32, y as i32);
+                    if stencil.matches_at(grid, top_left) {
+                        placements.push(Placement { top_left, variant });
+                    }
+                }
+            }
+        }
+        placements
+    }
 }
 
-pub fn detect_x_mas_at(grid: &[Vec<char>], x: usize, y: usize) -> bool {
-    let x_max = grid.len() - 1;
-    let y_max = grid.len() - 1;
+impl FromStr for Stencil {
+    type Err = ParseError;
 
-    if x + 2 > x_max || y + 2 > y_max {
-        return false;
+    fn from_str(pattern: &str) -> Result<Stencil, ParseError> {
+        let rows: Vec<Vec<Option<char>>> = pattern
+            .split('/')
+            .map(|row| {
+                row.chars()
+                    .map(|ch| if ch == '.' { None } else { Some(ch) })
+                    .collect()
+            })
+            .collect();
+        for (column, row) in fields(pattern, "/") {
+            if row.is_empty() || row.chars().count() != rows[0].len() {
+                return Err(ParseError::unexpected(
+                    1,
+                    column,
+                    row,
+                    &format!("a row of {} cells", rows[0].len().max(1)),
+                ));
+            }
+        }
+        Ok(Stencil { rows })
     }
-    if grid[y + 1][x + 1] != 'A' {
-        return false;
+}
+
+pub struct Dec04;
+
+impl Solution for Dec04 {
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+        input.parse()
     }
-    if !((grid[y][x] == 'M' && grid[y + 2][x + 2] == 'S')
-        || (grid[y][x] == 'S' && grid[y + 2][x + 2] == 'M'))
-    {
-        return false;
+
+    fn part1(grid: &Grid<char>) -> String {
+        WordSearch::new(&["XMAS"]).count(grid).to_string()
     }
-    if !((grid[y][x + 2] == 'M' && grid[y + 2][x] == 'S')
-        || (grid[y][x + 2] == 'S' && grid[y + 2][x] == 'M'))
-    {
-        return false;
+
+    fn part2(grid: &Grid<char>) -> Option<String> {
+        let x_mas: Stencil = "M.S/.A./M.S".parse().unwrap();
+        Some(x_mas.find(grid).len().to_string())
     }
-    true
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn can_extract_lines() {
-        let grid = vec![
-            vec!['a', 'b', 'c'],
-            vec!['d', 'e', 'f'],
-            vec!['g', 'h', 'i'],
-        ];
-
-        let extracted_strings = extract_lines(&grid);
-        // horizontals
-        assert!(extracted_strings.contains(&String::from("abc")));
-        assert!(extracted_strings.contains(&String::from("def")));
-        assert!(extracted_strings.contains(&String::from("ghi")));
-        // verticals
-        assert!(extracted_strings.contains(&String::from("adg")));
-        assert!(extracted_strings.contains(&String::from("beh")));
-        assert!(extracted_strings.contains(&String::from("cfi")));
-        // diagonals
-        assert!(extracted_strings.contains(&String::from("aei")));
-        assert!(extracted_strings.contains(&String::from("bf")));
-        assert!(extracted_strings.contains(&String::from("c")));
-        assert!(extracted_strings.contains(&String::from("dh")));
-        assert!(extracted_strings.contains(&String::from("g")));
-        assert!(extracted_strings.contains(&String::from("gec")));
-        assert!(extracted_strings.contains(&String::from("hf")));
-        assert!(extracted_strings.contains(&String::from("i")));
-        assert!(extracted_strings.contains(&String::from("db")));
-        assert!(extracted_strings.contains(&String::from("a")));
+    fn count_xmas(line: &str) -> usize {
+        let grid: Grid<char> = line.parse().unwrap();
+        WordSearch::new(&["XMAS"]).count(&grid)
     }
 
     #[test]
     fn can_count_xmas() {
-        assert_eq!(2, count_xmas(&String::from("XMASAMX.MM")));
-        assert_eq!(0, count_xmas(&String::from("XMA")));
-        assert_eq!(3, count_xmas(&String::from("XMASAMX.XMASM")));
+        assert_eq!(2, count_xmas("XMASAMX.MM"));
+        assert_eq!(0, count_xmas("XMA"));
+        assert_eq!(3, count_xmas("XMASAMX.XMASM"));
+    }
+
+    #[test]
+    fn can_find_words_in_all_directions() {
+        let grid: Grid<char> = "S..S..S\n.A.A.A.\n..MMM..\nSAMXMAS\n..MMM..\n.A.A.A.\nS..S..S"
+            .parse()
+            .unwrap();
+        let matches = WordSearch::new(&["XMAS"]).find(&grid);
+        assert_eq!(8, matches.len());
+        for direction in Direction::ALL {
+            assert!(matches.contains(&Match {
+                word: 0,
+                start: Point::new(3, 3),
+                direction
+            }));
+        }
+    }
+
+    #[test]
+    fn can_find_many_words_at_once() {
+        // `he` is part of `she` and `hers` and shows up once more diagonally, `his` only backwards
+        let grid: Grid<char> = "ushers\nsih...".parse().unwrap();
+        let search = WordSearch::new(&["he", "she", "his", "hers"]);
+        let mut found: Vec<(&str, Point, Direction)> = search
+            .find(&grid)
+            .iter()
+            .map(|m| (search.word(m.word), m.start, m.direction))
+            .collect();
+        found.sort_by_key(|&(word, start, _)| (word, start.x, start.y));
+        assert_eq!(
+            vec![
+                ("he", Point::new(2, 0), Direction::Right),
+                ("he", Point::new(2, 1), Direction::UpRight),
+                ("hers", Point::new(2, 0), Direction::Right),
+                ("his", Point::new(2, 1), Direction::Left),
+                ("she", Point::new(1, 0), Direction::Right),
+            ],
+            found
+        );
+    }
+
+    #[test]
+    fn can_find_one_letter_words_once() {
+        let grid: Grid<char> = "ABA\n.A.".parse().unwrap();
+        let search = WordSearch::new(&["A", "AB"]);
+        assert_eq!(3 + 3, search.count(&grid));
+        let matches = search.find(&grid);
+        assert!(matches
+            .iter()
+            .filter(|m| m.word == 0)
+            .all(|m| m.direction == Direction::Right));
+    }
+
+    fn x_mas() -> Stencil {
+        "M.S/.A./M.S".parse().unwrap()
+    }
+
+    #[test]
+    fn can_parse_stencil() {
+        let stencil = x_mas();
+        assert_eq!((3, 3), (stencil.width(), stencil.height()));
+        assert_eq!(
+            Err(ParseError::unexpected(1, 5, ".A", "a row of 3 cells")),
+            "M.S/.A/M.S".parse::<Stencil>()
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(1, 1, "", "a row of 1 cells")),
+            "".parse::<Stencil>()
+        );
+    }
+
+    #[test]
+    fn can_rotate_and_reflect_stencil() {
+        let stencil: Stencil = "AB./..C".parse().unwrap();
+        assert_eq!(".A/.B/C.".parse::<Stencil>(), Ok(stencil.rotate()));
+        assert_eq!(".BA/C..".parse::<Stencil>(), Ok(stencil.reflect()));
+        assert_eq!(8, stencil.variants().len());
+        // the X can only be turned, mirroring it gives one of the turns
+        assert_eq!(4, x_mas().variants().len());
     }
 
     #[test]
     fn can_detect_x_mas_at() {
-        let grid: Vec<Vec<char>> = vec![
-            vec!['.', '.', '.', '.', '.'],
-            vec!['.', 'M', '.', 'S', '.'],
-            vec!['.', '.', 'A', '.', '.'],
-            vec!['.', 'M', '.', 'S', '.'],
-            vec!['.', '.', '.', '.', '.'],
-        ];
-        assert!(detect_x_mas_at(&grid, 1, 1));
-        assert!(!detect_x_mas_at(&grid, 0, 0));
-        assert!(!detect_x_mas_at(&grid, 4, 4));
-        assert!(!detect_x_mas_at(&grid, 0, 3));
-        assert!(!detect_x_mas_at(&grid, 3, 0));
+        let grid: Grid<char> = ".....\n.M.S.\n..A..\n.M.S.\n.....".parse().unwrap();
+        let stencil = x_mas();
+        let placements = stencil.find(&grid);
+        assert_eq!(1, placements.len());
+        assert_eq!(Point::new(1, 1), placements[0].top_left);
+        let variant = &stencil.variants()[placements[0].variant];
+        assert!(variant.matches_at(&grid, Point::new(1, 1)));
+        assert!(!variant.matches_at(&grid, Point::new(0, 0)));
+        assert!(!variant.matches_at(&grid, Point::new(4, 4)));
+        assert!(!variant.matches_at(&grid, Point::new(0, 3)));
+        assert!(!variant.matches_at(&grid, Point::new(3, 0)));
+    }
+
+    #[test]
+    fn can_detect_x_mas_on_wide_grid() {
+        let grid: Grid<char> = "....M.S\n.....A.\n....M.S".parse().unwrap();
+        assert_eq!(
+            vec![Point::new(4, 0)],
+            x_mas()
+                .find(&grid)
+                .iter()
+                .map(|placement| placement.top_left)
+                .collect::<Vec<Point>>()
+        );
+        // taller than wide, the bar only fits once it's turned
+        let grid: Grid<char> = "S\nA\nM\nX".parse().unwrap();
+        let bar: Stencil = "XMAS".parse().unwrap();
+        assert_eq!(
+            vec![Placement {
+                top_left: Point::new(0, 0),
+                variant: 3
+            }],
+            bar.find(&grid)
+        );
     }
 }
@@ -1,25 +1,24 @@
 use std::fs;
 
-use dec04::{count_xmas, detect_x_mas_at, extract_lines};
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec04::Dec04;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let mut grid: Vec<Vec<char>> = Vec::new();
-    for line in input.lines() {
-        grid.push(line.chars().collect());
-    }
+    let parsed = stats.measure("parse", || Dec04::parse(&input).or_exit("input.txt"));
 
-    let extracted_lines = extract_lines(&grid);
-    let xmas_count: i32 = extracted_lines.into_iter().map(|l| count_xmas(&l)).sum();
-    println!("part1: {:?}", xmas_count);
+    let answer = stats.measure("part 1", || Dec04::part1(&parsed));
+    println!("part1: {}", answer);
 
-    let mut x_mas_count = 0;
-    for x in 0..(grid[0].len() - 1) {
-        for y in 0..(grid.len() - 1) {
-            if detect_x_mas_at(&grid, x, y) {
-                x_mas_count += 1;
-            }
-        }
+    let answer = stats.measure("part 2", || Dec04::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("part2: {}", answer);
     }
-    println!("part2: {:?}", x_mas_count);
+
+    stats.report();
 }
@@ -1,4 +1,11 @@
-#[derive(Debug)]
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use aoc_core::{fields, parse_field, ParseError, Solution};
+
+#[derive(Debug, Clone, Copy)]
 pub struct Rule {
     pub front: i32,
     pub back: i32,
@@ -10,6 +17,27 @@ impl PartialEq for Rule {
     }
 }
 
+impl FromStr for Rule {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Rule, ParseError> {
+        let pages: Vec<(usize, &str)> = fields(line, "|").collect();
+        match pages[..] {
+            [(front_column, front), (back_column, back)] => Ok(Rule {
+                front: parse_field(front, 1, front_column, "a page number")?,
+                back: parse_field(back, 1, back_column, "a page number")?,
+            }),
+            _ => Err(ParseError::unexpected(1, 1, line, "a rule like 47|53")),
+        }
+    }
+}
+
+pub fn parse_update(line: &str) -> Result<Vec<i32>, ParseError> {
+    fields(line, ",")
+        .map(|(column, page)| parse_field(page, 1, column, "a page number"))
+        .collect()
+}
+
 pub fn filter_relevant_rules<'a>(update: &[i32], rules: &'a Vec<Rule>) -> Vec<&'a Rule> {
     let mut relevant_rules: Vec<&'a Rule> = Vec::new();
 
@@ -27,40 +55,462 @@ pub fn validate_update(update: &[i32], rules: &[&Rule]) -> bool {
     for i in 0..update_len {
         // check everything on the left
         let page_number = update[i];
-        if i > 0 {
-            let page_numbers_left: Vec<i32> = rules
+        let page_numbers_left: Vec<i32> = rules
+            .iter()
+            .filter(|&r| r.back == page_number)
+            .map(|r| r.front)
+            .collect();
+        for page_number_left in page_numbers_left {
+            if !update[0..i].contains(&page_number_left) {
+                return false;
+            }
+        }
+        // check everything on the right
+        let page_numbers_right: Vec<i32> = rules
+            .iter()
+            .filter(|&r| r.front == page_number)
+            .map(|r| r.back)
+            .collect();
+        for page_number_right in page_numbers_right {
+            if !update[i..update_len].contains(&page_number_right) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+// all rules, indexed by the page that has to come first
+#[derive(Debug, Default, Clone)]
+pub struct RuleSet {
+    after: HashMap<i32, HashSet<i32>>,
+}
+
+impl RuleSet {
+    pub fn new(rules: &[Rule]) -> RuleSet {
+        let mut after: HashMap<i32, HashSet<i32>> = HashMap::new();
+        for rule in rules {
+            after.entry(rule.front).or_default().insert(rule.back);
+        }
+        RuleSet { after }
+    }
+
+    // whether there is a rule `front|back`
+    pub fn must_precede(&self, front: i32, back: i32) -> bool {
+        self.after
+            .get(&front)
+            .is_some_and(|backs| backs.contains(&back))
+    }
+
+    // no page comes after one that it has to precede
+    pub fn is_valid(&self, update: &[i32]) -> bool {
+        update.iter().enumerate().all(|(i, &page)| {
+            update[..i]
                 .iter()
-                .filter(|&r| r.back == page_number)
-                .map(|r| r.front)
-                .collect();
-            for page_number_left in page_numbers_left {
-                if !update[0..i].contains(&page_number_left) {
-                    return false;
+                .all(|&earlier| !self.must_precede(page, earlier))
+        })
+    }
+
+    // the rules between pages of the update, for `reorder_update`
+    pub fn rules_within(&self, update: &[i32]) -> Vec<Rule> {
+        let mut pages: Vec<i32> = update.to_vec();
+        pages.sort();
+        pages.dedup();
+        let mut rules: Vec<Rule> = Vec::new();
+        for &front in &pages {
+            for &back in &pages {
+                if self.must_precede(front, back) {
+                    rules.push(Rule { front, back });
                 }
             }
         }
-        // check everything on the right
-        if i < update_len - 1 {
-            let page_numbers_right: Vec<i32> = rules
+        rules
+    }
+
+    // `reorder_update` with the rules between pages of the update
+    pub fn reorder(&self, update: &[i32]) -> Result<Vec<i32>, Cycle> {
+        let rules_within = self.rules_within(update);
+        let relevant_rules: Vec<&Rule> = rules_within.iter().collect();
+        reorder_update(update, &relevant_rules)
+    }
+
+    // for `sort_by`. pages without a rule between them are `Equal`, so this only sorts
+    // correctly if there is a rule for every pair, like in the puzzle. `reorder_update` works
+    // with any rules.
+    pub fn compare(&self, a: i32, b: i32) -> Ordering {
+        if self.must_precede(a, b) {
+            Ordering::Less
+        } else if self.must_precede(b, a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+// a rule that an update breaks, `front` and `back` are the positions of its pages in the
+// update, the back page comes first.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Violation {
+    pub rule: Rule,
+    pub front: usize,
+    pub back: usize,
+}
+
+// audits of the whole rule set. "reaches" means there is a chain of rules from one page to
+// the other.
+impl RuleSet {
+    // every page that shows up in a rule, in order
+    pub fn pages(&self) -> Vec<i32> {
+        let pages: HashSet<i32> = self
+            .after
+            .iter()
+            .flat_map(|(&front, backs)| backs.iter().copied().chain([front]))
+            .collect();
+        let mut pages: Vec<i32> = pages.into_iter().collect();
+        pages.sort();
+        pages
+    }
+
+    // in the order of the pages in the update
+    pub fn violations(&self, update: &[i32]) -> Vec<Violation> {
+        let mut violations: Vec<Violation> = Vec::new();
+        for (back, &back_page) in update.iter().enumerate() {
+            for (front, &front_page) in update.iter().enumerate().skip(back + 1) {
+                if self.must_precede(front_page, back_page) {
+                    violations.push(Violation {
+                        rule: Rule {
+                            front: front_page,
+                            back: back_page,
+                        },
+                        front,
+                        back,
+                    });
+                }
+            }
+        }
+        violations
+    }
+
+    // the pages every page reaches, a page only reaches itself if it is part of a cycle
+    fn reachable(&self) -> HashMap<i32, HashSet<i32>> {
+        self.pages()
+            .into_iter()
+            .map(|page| {
+                let mut reached: HashSet<i32> = HashSet::new();
+                let mut queue: VecDeque<i32> = VecDeque::from([page]);
+                while let Some(current) = queue.pop_front() {
+                    for &next in self.after.get(&current).into_iter().flatten() {
+                        if reached.insert(next) {
+                            queue.push_back(next);
+                        }
+                    }
+                }
+                (page, reached)
+            })
+            .collect()
+    }
+
+    // one cycle for every group of pages that contradict each other, the shortest one through
+    // the smallest page of the group.
+    pub fn cycles(&self) -> Vec<Cycle> {
+        let reachable = self.reachable();
+        let mut covered: HashSet<i32> = HashSet::new();
+        let mut cycles: Vec<Cycle> = Vec::new();
+        for page in self.pages() {
+            if covered.contains(&page) || !reachable[&page].contains(&page) {
+                continue;
+            }
+            let group: HashSet<i32> = reachable[&page]
                 .iter()
-                .filter(|&r| r.front == page_number)
-                .map(|r| r.back)
+                .copied()
+                .filter(|other| reachable[other].contains(&page))
                 .collect();
-            for page_number_right in page_numbers_right {
-                if !update[i..update_len].contains(&page_number_right) {
-                    return false;
+            cycles.push(Cycle {
+                pages: self.shortest_cycle(page, &group),
+            });
+            covered.extend(group);
+        }
+        cycles
+    }
+
+    fn shortest_cycle(&self, page: i32, group: &HashSet<i32>) -> Vec<i32> {
+        let mut previous: HashMap<i32, i32> = HashMap::new();
+        let mut queue: VecDeque<i32> = VecDeque::from([page]);
+        while let Some(current) = queue.pop_front() {
+            // in order, so the same rules always give the same cycle
+            let mut nexts: Vec<i32> = self.after[&current].iter().copied().collect();
+            nexts.sort();
+            for next in nexts {
+                if !group.contains(&next) || previous.contains_key(&next) {
+                    continue;
+                }
+                previous.insert(next, current);
+                if next == page {
+                    queue.clear();
+                    break;
                 }
+                queue.push_back(next);
             }
         }
+        let mut cycle: Vec<i32> = vec![page];
+        let mut current = previous[&page];
+        while current != page {
+            cycle.push(current);
+            current = previous[&current];
+        }
+        cycle[1..].reverse();
+        cycle
     }
 
-    true
+    // rules that follow from the others anyway, `a|c` if there are `a|b` and `b|c`
+    pub fn redundant_rules(&self) -> Vec<Rule> {
+        let mut redundant: Vec<Rule> = Vec::new();
+        for front in self.pages() {
+            let Some(backs) = self.after.get(&front) else {
+                continue;
+            };
+            let mut backs: Vec<i32> = backs.iter().copied().collect();
+            backs.sort();
+            for back in backs {
+                if self.reaches_without(front, back) {
+                    redundant.push(Rule { front, back });
+                }
+            }
+        }
+        redundant
+    }
+
+    // whether `front` reaches `back` without the rule `front|back` itself. the chain may pass
+    // through `front` again when the rules have cycles.
+    fn reaches_without(&self, front: i32, back: i32) -> bool {
+        let mut reached: HashSet<i32> = HashSet::new();
+        let mut queue: VecDeque<i32> = VecDeque::from([front]);
+        while let Some(current) = queue.pop_front() {
+            for &next in self.after.get(&current).into_iter().flatten() {
+                if current == front && next == back {
+                    continue;
+                }
+                if next == back {
+                    return true;
+                }
+                if reached.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+
+    // pages whose place relative to every other page is decided by the rules
+    pub fn totally_ordered(&self) -> Vec<i32> {
+        let reachable = self.reachable();
+        let pages = self.pages();
+        pages
+            .iter()
+            .copied()
+            .filter(|page| {
+                pages.iter().all(|other| {
+                    other == page
+                        || reachable[page].contains(other)
+                        || reachable[other].contains(page)
+                })
+            })
+            .collect()
+    }
+}
+
+// pages that the rules want in a circle, every page before the next one and the last one
+// before the first, so there is no order that satisfies all of them.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Cycle {
+    pub pages: Vec<i32>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pages: Vec<String> = self
+            .pages
+            .iter()
+            .chain(self.pages.first())
+            .map(|page| page.to_string())
+            .collect();
+        write!(
+            f,
+            "the rules contradict each other: {}",
+            pages.join(" before ")
+        )
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+// sorts the pages topologically by the rules. whenever more than one page could come next
+// the one that was first in the update wins, so an update that's valid already comes back
+// unchanged.
+pub fn reorder_update(update: &[i32], rules: &[&Rule]) -> Result<Vec<i32>, Cycle> {
+    // edges between positions in the update, from the page that has to come first
+    let mut after: Vec<Vec<usize>> = vec![Vec::new(); update.len()];
+    let mut before_count: Vec<usize> = vec![0; update.len()];
+    for rule in rules {
+        for (front, _) in update.iter().enumerate().filter(|(_, &p)| p == rule.front) {
+            for (back, _) in update.iter().enumerate().filter(|(_, &p)| p == rule.back) {
+                after[front].push(back);
+                before_count[back] += 1;
+            }
+        }
+    }
+
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..update.len())
+        .filter(|&i| before_count[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut reordered: Vec<i32> = Vec::with_capacity(update.len());
+    while let Some(Reverse(i)) = ready.pop() {
+        reordered.push(update[i]);
+        for &next in &after[i] {
+            before_count[next] -= 1;
+            if before_count[next] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+
+    if reordered.len() < update.len() {
+        return Err(find_cycle(update, &after, &before_count));
+    }
+    Ok(reordered)
+}
+
+// every position that's left over still waits for another one that's left over, so walking
+// backwards from any of them has to run in circles eventually.
+fn find_cycle(update: &[i32], after: &[Vec<usize>], before_count: &[usize]) -> Cycle {
+    let mut before: Vec<Option<usize>> = vec![None; update.len()];
+    for (i, nexts) in after.iter().enumerate() {
+        for &next in nexts {
+            if before_count[i] > 0 && before_count[next] > 0 {
+                before[next] = Some(i);
+            }
+        }
+    }
+
+    let mut seen: Vec<Option<usize>> = vec![None; update.len()];
+    let mut path: Vec<usize> = Vec::new();
+    let mut current = (0..update.len()).find(|&i| before_count[i] > 0).unwrap();
+    while seen[current].is_none() {
+        seen[current] = Some(path.len());
+        path.push(current);
+        current = before[current].unwrap();
+    }
+    let mut cycle: Vec<i32> = path[seen[current].unwrap()..]
+        .iter()
+        .map(|&i| update[i])
+        .collect();
+    cycle.reverse();
+    Cycle { pages: cycle }
+}
+
+pub struct Dec05;
+
+pub struct PrintQueue {
+    pub rules: Vec<Rule>,
+    pub updates: Vec<Vec<i32>>,
+}
+
+impl Solution for Dec05 {
+    type Input = PrintQueue;
+
+    // the rules, a blank line, then the updates. every line in a section has to parse, and the
+    // rules have to put the pages of every update in some order. the rules as a whole don't,
+    // the puzzle's contradict each other across updates.
+    fn parse(input: &str) -> Result<PrintQueue, ParseError> {
+        let mut rules: Vec<Rule> = Vec::new();
+        let mut updates: Vec<Vec<i32>> = Vec::new();
+        let mut update_lines: Vec<(usize, &str)> = Vec::new();
+        let mut in_updates = false;
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                in_updates = true;
+            } else if in_updates {
+                updates.push(parse_update(line).map_err(|e| e.shift(i, 0))?);
+                update_lines.push((i, line));
+            } else {
+                rules.push(line.parse().map_err(|e: ParseError| e.shift(i, 0))?);
+            }
+        }
+
+        let rule_set = RuleSet::new(&rules);
+        for (update, (i, line)) in updates.iter().zip(update_lines) {
+            if let Err(cycle) = rule_set.reorder(update) {
+                return Err(ParseError::unexpected(
+                    i + 1,
+                    1,
+                    line,
+                    &format!("pages that can be put in order, {}", cycle),
+                ));
+            }
+        }
+        Ok(PrintQueue { rules, updates })
+    }
+
+    fn part1(queue: &PrintQueue) -> String {
+        let rules = RuleSet::new(&queue.rules);
+        let sum: i32 = queue
+            .updates
+            .iter()
+            .filter(|update| rules.is_valid(update))
+            .map(|update| update[update.len() / 2])
+            .sum();
+        sum.to_string()
+    }
+
+    fn part2(queue: &PrintQueue) -> Option<String> {
+        let rules = RuleSet::new(&queue.rules);
+        let sum: i32 = queue
+            .updates
+            .iter()
+            .filter(|update| !rules.is_valid(update))
+            // `parse` makes sure every update can be put in order
+            .filter_map(|update| rules.reorder(update).ok())
+            .map(|reordered| reordered[reordered.len() / 2])
+            .sum();
+        Some(sum.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn can_parse_rule() {
+        assert_eq!(
+            Rule {
+                front: 47,
+                back: 53
+            },
+            "47|53".parse().unwrap()
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(1, 4, "5x", "a page number")),
+            "47|5x".parse::<Rule>()
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(1, 1, "47|53|1", "a rule like 47|53")),
+            "47|53|1".parse::<Rule>()
+        );
+    }
+
+    #[test]
+    fn can_parse_update() {
+        assert_eq!(Ok(vec![75, 47, 61]), parse_update("75,47,61"));
+        assert_eq!(
+            Err(ParseError::unexpected(1, 4, "", "a page number")),
+            parse_update("75,,61")
+        );
+    }
+
     #[test]
     fn can_filter_relevant_rules() {
         let rules = vec![
@@ -161,4 +611,257 @@ mod tests {
         let update = vec![75, 97, 47, 61, 53];
         assert!(!validate_update(&update, &rules));
     }
+
+    #[test]
+    fn can_validate_first_and_last_page() {
+        let rules = vec![&Rule {
+            front: 97,
+            back: 13,
+        }];
+        assert!(!validate_update(&[13, 97], &rules));
+        assert!(!validate_update(&[13, 75, 97], &rules));
+        assert!(validate_update(&[97, 75, 13], &rules));
+    }
+
+    fn rules(pairs: &[(i32, i32)]) -> Vec<Rule> {
+        pairs
+            .iter()
+            .map(|&(front, back)| Rule { front, back })
+            .collect()
+    }
+
+    #[test]
+    fn can_reorder_update() {
+        let rules = rules(&[
+            (97, 13),
+            (97, 47),
+            (75, 29),
+            (29, 13),
+            (97, 29),
+            (47, 13),
+            (75, 47),
+            (97, 75),
+            (47, 29),
+            (75, 13),
+        ]);
+        let relevant_rules: Vec<&Rule> = rules.iter().collect();
+        assert_eq!(
+            Ok(vec![97, 75, 47, 29, 13]),
+            reorder_update(&[97, 13, 75, 29, 47], &relevant_rules)
+        );
+        assert_eq!(
+            Ok(vec![97, 75, 47, 29, 13]),
+            reorder_update(&[13, 29, 47, 75, 97], &relevant_rules)
+        );
+        // 5 and 6 don't have rules and come as early as they can
+        assert_eq!(
+            Ok(vec![5, 6, 97, 13]),
+            reorder_update(&[5, 13, 6, 97], &relevant_rules)
+        );
+    }
+
+    #[test]
+    fn can_detect_cycles() {
+        let rules = rules(&[(1, 2), (2, 3), (3, 1), (4, 1)]);
+        let relevant_rules: Vec<&Rule> = rules.iter().collect();
+        let cycle = reorder_update(&[4, 3, 2, 1, 5], &relevant_rules).unwrap_err();
+        let mut pages = cycle.pages.clone();
+        pages.sort();
+        assert_eq!(vec![1, 2, 3], pages);
+        let start = cycle.pages.iter().position(|&p| p == 1).unwrap();
+        assert_eq!(
+            vec![1, 2, 3],
+            [&cycle.pages[start..], &cycle.pages[..start]].concat()
+        );
+        assert_eq!(
+            "the rules contradict each other: 1 before 2 before 3 before 1",
+            Cycle {
+                pages: vec![1, 2, 3]
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn can_query_rule_set() {
+        let rules = RuleSet::new(&rules(&[
+            (47, 53),
+            (97, 13),
+            (97, 47),
+            (75, 47),
+            (97, 53),
+            (47, 13),
+            (53, 13),
+        ]));
+        assert!(rules.must_precede(47, 53));
+        assert!(!rules.must_precede(53, 47));
+        assert!(!rules.must_precede(13, 99));
+        assert!(rules.is_valid(&[75, 97, 47, 61, 53]));
+        assert!(!rules.is_valid(&[75, 53, 47]));
+        assert!(!rules.is_valid(&[13, 97]));
+        assert!(rules.is_valid(&[]));
+
+        let mut update = vec![53, 13, 47, 97];
+        update.sort_by(|&a, &b| rules.compare(a, b));
+        assert_eq!(vec![97, 47, 53, 13], update);
+    }
+
+    #[test]
+    fn can_reorder_with_incomplete_rules() {
+        let rules = RuleSet::new(&rules(&[(3, 1), (75, 47), (47, 61)]));
+        assert_eq!(
+            vec![Rule { front: 3, back: 1 }],
+            rules.rules_within(&[1, 2, 3])
+        );
+        assert_eq!(
+            vec![
+                Rule {
+                    front: 47,
+                    back: 61
+                },
+                Rule {
+                    front: 75,
+                    back: 47
+                }
+            ],
+            rules.rules_within(&[61, 47, 75, 47])
+        );
+        // 2 has no rule with either page, it must not end up in the middle
+        let queue = Dec05::parse("3|1\n\n1,2,3\n").unwrap();
+        assert_eq!(Some("3".to_string()), Dec05::part2(&queue));
+    }
+
+    #[test]
+    fn can_validate_like_relevant_rules() {
+        let all = rules(&[(75, 47), (97, 75), (47, 61), (61, 53), (29, 13), (97, 13)]);
+        let rule_set = RuleSet::new(&all);
+        for update in [
+            vec![75, 47, 61, 53, 29],
+            vec![97, 75, 47, 13],
+            vec![75, 97, 47, 61, 53],
+            vec![61, 13, 29],
+            vec![13, 97],
+        ] {
+            let relevant_rules = filter_relevant_rules(&update, &all);
+            assert_eq!(
+                validate_update(&update, &relevant_rules),
+                rule_set.is_valid(&update),
+                "{:?}",
+                update
+            );
+        }
+    }
+
+    #[test]
+    fn can_list_violations() {
+        let rules = RuleSet::new(&rules(&[(97, 75), (75, 47), (97, 47), (61, 53)]));
+        assert_eq!(
+            vec![
+                Violation {
+                    rule: Rule {
+                        front: 97,
+                        back: 75
+                    },
+                    front: 2,
+                    back: 0
+                },
+                Violation {
+                    rule: Rule {
+                        front: 97,
+                        back: 47
+                    },
+                    front: 2,
+                    back: 1
+                },
+            ],
+            rules.violations(&[75, 47, 97, 61, 53])
+        );
+        assert!(rules.violations(&[97, 75, 47, 61, 53]).is_empty());
+    }
+
+    #[test]
+    fn can_find_cycles_in_rule_set() {
+        let rule_set = RuleSet::new(&rules(&[
+            (3, 1),
+            (1, 2),
+            (2, 3),
+            (2, 4),
+            (4, 1),
+            (5, 6),
+            (7, 8),
+            (8, 7),
+            (9, 9),
+        ]));
+        assert_eq!(
+            vec![
+                Cycle {
+                    pages: vec![1, 2, 3]
+                },
+                Cycle { pages: vec![7, 8] },
+                Cycle { pages: vec![9] },
+            ],
+            rule_set.cycles()
+        );
+        let rule_set = RuleSet::new(&rules(&[(1, 2), (2, 3), (1, 3)]));
+        assert!(rule_set.cycles().is_empty());
+    }
+
+    #[test]
+    fn can_find_redundant_rules() {
+        let rule_set = RuleSet::new(&rules(&[(1, 2), (2, 3), (1, 3), (3, 4), (1, 4), (5, 4)]));
+        assert_eq!(
+            vec![Rule { front: 1, back: 3 }, Rule { front: 1, back: 4 }],
+            rule_set.redundant_rules()
+        );
+
+        // going around a cycle back to the front page doesn't make a rule redundant
+        let rule_set = RuleSet::new(&rules(&[(1, 2), (2, 1), (1, 3)]));
+        assert!(rule_set.redundant_rules().is_empty());
+        let rule_set = RuleSet::new(&rules(&[(9, 9), (9, 3)]));
+        assert!(rule_set.redundant_rules().is_empty());
+        let rule_set = RuleSet::new(&rules(&[(1, 2), (2, 3), (3, 1), (1, 3)]));
+        assert_eq!(vec![Rule { front: 1, back: 3 }], rule_set.redundant_rules());
+    }
+
+    #[test]
+    fn can_find_totally_ordered_pages() {
+        // 1 and 4 are before or after everything else, 2 and 3 don't know about each other
+        let rules = RuleSet::new(&rules(&[(1, 2), (1, 3), (2, 4), (3, 4)]));
+        assert_eq!(vec![1, 4], rules.totally_ordered());
+        assert_eq!(vec![1, 2, 3, 4], rules.pages());
+    }
+
+    #[test]
+    fn can_parse_print_queue() {
+        let queue = Dec05::parse("47|53\n97|13\n\n75,47,61\n").unwrap();
+        assert_eq!(2, queue.rules.len());
+        assert_eq!(vec![vec![75, 47, 61]], queue.updates);
+        assert_eq!(
+            Err(ParseError::unexpected(4, 4, "4x", "a page number")),
+            Dec05::parse("47|53\n97|13\n\n75,4x,61\n").map(|_| ())
+        );
+
+        let queue = Dec05::parse("1|2\n\n2,1\n7\n").unwrap();
+        assert_eq!(vec![vec![2, 1], vec![7]], queue.updates);
+        assert_eq!(
+            Err(ParseError::unexpected(5, 1, "foo", "a page number")),
+            Dec05::parse("1|2\n\n2,1\n7\nfoo\n").map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::unexpected(2, 1, "foo", "a rule like 47|53")),
+            Dec05::parse("1|2\nfoo\n\n2,1\n").map(|_| ())
+        );
+
+        // the rules contradict each other only across updates, that's fine
+        assert!(Dec05::parse("1|2\n2|3\n3|1\n\n1,2\n2,3\n").is_ok());
+        assert_eq!(
+            Err(ParseError::unexpected(
+                5,
+                1,
+                "2,1",
+                "pages that can be put in order, the rules contradict each other: 1 before 2 before 1"
+            )),
+            Dec05::parse("1|2\n2|1\n\n1\n2,1\n").map(|_| ())
+        );
+    }
 }
@@ -1,37 +1,24 @@
 use std::fs;
 
-use dec05::{filter_relevant_rules, validate_update, Rule};
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec05::Dec05;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let mut rules: Vec<Rule> = Vec::new();
-    let mut updates: Vec<Vec<i32>> = Vec::new();
-
-    for line in input.lines() {
-        if line.contains('|') {
-            let parts: Vec<&str> = line.split("|").collect();
-            rules.push(Rule {
-                front: parts[0].parse().unwrap(),
-                back: parts[1].parse().unwrap(),
-            })
-        }
-        if line.contains(',') {
-            updates.push(line.split(',').map(|n| n.parse().unwrap()).collect());
-        }
-    }
+    let parsed = stats.measure("parse", || Dec05::parse(&input).or_exit("input.txt"));
 
-    let valid_updates: &Vec<Vec<i32>> = &updates
-        .into_iter()
-        .filter(|update| {
-            let relevant_rules = filter_relevant_rules(update, &rules);
-            validate_update(update, &relevant_rules)
-        })
-        .collect();
+    let answer = stats.measure("part 1", || Dec05::part1(&parsed));
+    println!("part 1: {}", answer);
 
-    let middle_page_sum: i32 = valid_updates
-        .iter()
-        .map(|update| update[update.len() / 2])
-        .sum();
+    let answer = stats.measure("part 2", || Dec05::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("part 2: {}", answer);
+    }
 
-    println!("{:?}", middle_page_sum);
+    stats.report();
 }
@@ -1,10 +1,21 @@
-pub fn build_map(lines: Vec<&str>) -> Map {
-    let mut points: Vec<Vec<Cell>> = Vec::new();
-    let mut player: Option<Player> = None;
+use std::str::FromStr;
 
-    for (y, line) in lines.iter().enumerate() {
-        let mut row: Vec<Cell> = Vec::new();
-        for (x, ch) in line.chars().enumerate() {
+use aoc_core::{ParseError, Solution};
+use aoc_grid::{Direction, Grid, Point};
+
+#[derive(Clone)]
+pub struct Map {
+    points: Grid<Cell>,
+    player: Player,
+    start: Point,
+    jumps: JumpTable,
+}
+
+impl FromStr for Map {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Map, ParseError> {
+        let points: Grid<Cell> = Grid::parse(input, |ch| {
             let cell = match ch {
                 '^' => Cell {
                     visited: true,
@@ -23,83 +34,51 @@ pub fn build_map(lines: Vec<&str>) -> Map {
                     content: CellContent::Empty,
                 },
             };
-            if ch == '^' {
-                player = Some(Player {
-                    x,
-                    y,
-                    direction: PlayerFacing::Up,
-                });
-            }
-            row.push(cell);
-        }
-        points.push(row);
-    }
-    let player = player.expect("Player not found in the map");
+            Some(cell)
+        })?;
+        let position = points
+            .position(|c| c.content == CellContent::Player)
+            .ok_or_else(|| ParseError::missing(points.height() + 1, 1, "a player '^'"))?;
+        let player = Player {
+            position,
+            direction: Direction::Up,
+        };
 
-    Map { points, player }
-}
+        let jumps = JumpTable::new(&points);
 
-pub struct Map {
-    points: Vec<Vec<Cell>>,
-    player: Player,
+        Ok(Map {
+            points,
+            player,
+            start: position,
+            jumps,
+        })
+    }
 }
 
 impl Map {
-    fn at(&self, x: usize, y: usize) -> &Cell {
-        &self.points[y][x]
+    fn turn_player(&mut self) {
+        self.player.direction = self.player.direction.turn_right();
     }
 
-    fn turn_player(&mut self) {
-        match &self.player.direction {
-            PlayerFacing::Up => {
-                self.player.direction = PlayerFacing::Right;
-            }
-            PlayerFacing::Right => {
-                self.player.direction = PlayerFacing::Down;
-            }
-            PlayerFacing::Down => {
-                self.player.direction = PlayerFacing::Left;
-            }
-            PlayerFacing::Left => {
-                self.player.direction = PlayerFacing::Up;
-            }
+    // puts the player back where they started, the cells they've visited stay visited
+    pub fn reset_player(&mut self) {
+        self.player = Player {
+            position: self.start,
+            direction: Direction::Up,
         };
     }
 
     pub fn move_player(&mut self) -> bool {
-        let (next_x, next_y) = match self.player.direction {
-            PlayerFacing::Up => {
-                if self.player.y == 0 {
-                    return false;
-                }
-                (self.player.x, self.player.y - 1)
-            }
-            PlayerFacing::Right => {
-                if self.player.x == (self.points[0].len() - 1) {
-                    return false;
-                }
-                (self.player.x + 1, self.player.y)
-            }
-            PlayerFacing::Down => {
-                if self.player.y == self.points.len() - 1 {
-                    return false;
-                }
-                (self.player.x, self.player.y + 1)
-            }
-            PlayerFacing::Left => {
-                if self.player.x == 0 {
-                    return false;
-                }
-                (self.player.x - 1, self.player.y)
-            }
-        };
+        let next = self.player.position.step(self.player.direction);
 
-        let next_cell = &(self.points[next_y][next_x]);
+        let next_cell = match self.points.get(next) {
+            Some(cell) => cell,
+            None => return false,
+        };
         match next_cell.content {
             CellContent::Empty | CellContent::Player => {
-                self.player.y = next_y;
-                self.player.x = next_x;
-                self.points[self.player.y][self.player.x].visited = true;
+                self.player.position = next;
+                self.points[next].visited = true;
             }
             CellContent::Obstacle => {
                 self.turn_player();
@@ -110,37 +89,239 @@ impl Map {
     }
 
     pub fn visited_count(&self) -> i32 {
+        self.points.iter().filter(|(_, c)| c.visited).count() as i32
+    }
+
+    pub fn visited(&self) -> Vec<Point> {
         self.points
             .iter()
-            .map(|row| row.iter().filter(|&c| c.visited).count() as i32)
-            .sum()
+            .filter(|(_, c)| c.visited)
+            .map(|(point, _)| point)
+            .collect()
     }
+
+    // returns false if there's no empty cell at the point, the player's cell doesn't count
+    pub fn add_obstacle(&mut self, point: Point) -> bool {
+        match self.points.get_mut(point) {
+            Some(cell) if cell.content == CellContent::Empty => {
+                cell.content = CellContent::Obstacle;
+                self.jumps.add_obstacle(&self.points, point);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // returns false if there's no obstacle at the point
+    pub fn remove_obstacle(&mut self, point: Point) -> bool {
+        match self.points.get_mut(point) {
+            Some(cell) if cell.content == CellContent::Obstacle => {
+                cell.content = CellContent::Empty;
+                self.jumps.remove_obstacle(&self.points, point);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // moves the player straight up to the next obstacle and turns, or returns false if there is
+    // none and they'd leave the map. unlike `move_player` the cells in between aren't marked as
+    // visited.
+    pub fn jump_player(&mut self) -> bool {
+        let direction = self.player.direction;
+        match self.jumps.next_obstacle(self.player.position, direction) {
+            Some(obstacle) => {
+                self.player.position = obstacle.step(direction.opposite());
+                self.turn_player();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // moves the player until they leave the map or end up in a state they've been in before,
+    // a state being the position and the direction they're facing.
+    pub fn walk(&mut self) -> WalkOutcome {
+        let mut seen: Grid<u8> = Grid::new(self.points.width(), self.points.height(), 0);
+        loop {
+            let facing = facing_bit(self.player.direction);
+            let seen = &mut seen[self.player.position];
+            if *seen & facing != 0 {
+                return WalkOutcome::Looped;
+            }
+            *seen |= facing;
+            if !self.move_player() {
+                return WalkOutcome::Exited;
+            }
+        }
+    }
+
+    // the same as `walk`, but jumps from turn to turn. only the cells the player turns on are
+    // marked as visited.
+    pub fn jump_walk(&mut self) -> WalkOutcome {
+        let mut seen: Grid<u8> = Grid::new(self.points.width(), self.points.height(), 0);
+        loop {
+            let facing = facing_bit(self.player.direction);
+            let seen = &mut seen[self.player.position];
+            if *seen & facing != 0 {
+                return WalkOutcome::Looped;
+            }
+            *seen |= facing;
+            if !self.jump_player() {
+                return WalkOutcome::Exited;
+            }
+            self.points[self.player.position].visited = true;
+        }
+    }
+}
+
+// the closest obstacle in front of every cell, in each of the cardinal directions. `None` if
+// there is nothing but the edge of the map. obstacles have entries too, so they're still right
+// once an obstacle is removed.
+#[derive(PartialEq, Debug, Clone)]
+struct JumpTable {
+    next: Grid<[Option<Point>; 4]>,
 }
 
+impl JumpTable {
+    fn new(points: &Grid<Cell>) -> JumpTable {
+        let mut next = Grid::new(points.width(), points.height(), [None; 4]);
+        let forwards: Vec<Point> = points.points().collect();
+        let backwards: Vec<Point> = forwards.iter().rev().copied().collect();
+        for direction in Direction::CARDINAL {
+            let facing = facing_index(direction);
+            // the cell in front has to be done first, that's the earlier one going up or left
+            let order = match direction {
+                Direction::Up | Direction::Left => &forwards,
+                _ => &backwards,
+            };
+            for &point in order {
+                let ahead = point.step(direction);
+                next[point][facing] = match points.get(ahead) {
+                    Some(cell) if cell.content == CellContent::Obstacle => Some(ahead),
+                    Some(_) => next[ahead][facing],
+                    None => None,
+                };
+            }
+        }
+        JumpTable { next }
+    }
+
+    fn next_obstacle(&self, point: Point, direction: Direction) -> Option<Point> {
+        self.next[point][facing_index(direction)]
+    }
+
+    // `points` already has the new obstacle
+    fn add_obstacle(&mut self, points: &Grid<Cell>, obstacle: Point) {
+        for direction in Direction::CARDINAL {
+            self.update_behind(points, obstacle, direction, Some(obstacle));
+        }
+    }
+
+    // `points` already has the obstacle removed
+    fn remove_obstacle(&mut self, points: &Grid<Cell>, obstacle: Point) {
+        for direction in Direction::CARDINAL {
+            let beyond = self.next_obstacle(obstacle, direction);
+            self.update_behind(points, obstacle, direction, beyond);
+        }
+    }
+
+    // every cell that runs into `point` facing `direction` now sees `next` instead, up to and
+    // including the first obstacle behind it.
+    fn update_behind(
+        &mut self,
+        points: &Grid<Cell>,
+        point: Point,
+        direction: Direction,
+        next: Option<Point>,
+    ) {
+        let facing = facing_index(direction);
+        let mut behind = point.step(direction.opposite());
+        while let Some(cell) = points.get(behind) {
+            self.next[behind][facing] = next;
+            if cell.content == CellContent::Obstacle {
+                break;
+            }
+            behind = behind.step(direction.opposite());
+        }
+    }
+}
+
+fn facing_index(direction: Direction) -> usize {
+    Direction::CARDINAL
+        .iter()
+        .position(|&d| d == direction)
+        .expect("the player only faces cardinal directions")
+}
+
+fn facing_bit(direction: Direction) -> u8 {
+    1 << facing_index(direction)
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum WalkOutcome {
+    Exited,
+    Looped,
+}
+
+// every cell where a new obstacle traps the player in a loop. an obstacle can only change the
+// walk if it's on the path the player takes without it, so those are the only candidates.
+// every candidate is tried on the same map, the obstacle is removed again afterwards.
+pub fn loop_obstructions(map: &Map) -> Vec<Point> {
+    let mut candidate = map.clone();
+    candidate.walk();
+    candidate
+        .visited()
+        .into_iter()
+        .filter(|&point| {
+            if !candidate.add_obstacle(point) {
+                return false;
+            }
+            candidate.reset_player();
+            let outcome = candidate.jump_walk();
+            candidate.remove_obstacle(point);
+            outcome == WalkOutcome::Looped
+        })
+        .collect()
+}
+
+#[derive(Clone)]
 struct Cell {
     visited: bool,
     content: CellContent,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 enum CellContent {
     Player,
     Obstacle,
     Empty,
 }
 
+#[derive(Clone)]
 struct Player {
-    x: usize,
-    y: usize,
-    direction: PlayerFacing,
+    position: Point,
+    direction: Direction,
 }
 
-#[derive(PartialEq, Debug)]
-enum PlayerFacing {
-    Up,
-    Right,
-    Down,
-    Left,
+pub struct Dec06;
+
+impl Solution for Dec06 {
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Map, ParseError> {
+        input.parse()
+    }
+
+    fn part1(map: &Map) -> String {
+        let mut map = map.clone();
+        map.walk();
+        map.visited_count().to_string()
+    }
+
+    fn part2(map: &Map) -> Option<String> {
+        Some(loop_obstructions(map).len().to_string())
+    }
 }
 
 #[cfg(test)]
@@ -149,59 +330,172 @@ mod tests {
 
     #[test]
     fn can_build_map() {
-        let lines = vec![".#", "^."];
-        let map: Map = build_map(lines);
-        assert_eq!(CellContent::Empty, map.at(0, 0).content);
-        assert_eq!(CellContent::Obstacle, map.at(1, 0).content);
-        assert_eq!(CellContent::Player, map.at(0, 1).content);
-        assert_eq!(CellContent::Empty, map.at(1, 1).content);
-        assert_eq!(0, map.player.x);
-        assert_eq!(1, map.player.y);
+        let map: Map = ".#\n^.".parse().unwrap();
+        assert_eq!(CellContent::Empty, map.points[Point::new(0, 0)].content);
+        assert_eq!(CellContent::Obstacle, map.points[Point::new(1, 0)].content);
+        assert_eq!(CellContent::Player, map.points[Point::new(0, 1)].content);
+        assert_eq!(CellContent::Empty, map.points[Point::new(1, 1)].content);
+        assert_eq!(0, map.player.position.x);
+        assert_eq!(1, map.player.position.y);
+    }
+
+    #[test]
+    fn can_reject_map_without_player() {
+        let map = ".#\n..".parse::<Map>();
+        assert_eq!(Some(ParseError::missing(3, 1, "a player '^'")), map.err());
     }
 
     #[test]
     fn can_turn_player() {
-        let lines = vec![".#", "^."];
-        let mut map = build_map(lines);
-        assert_eq!(PlayerFacing::Up, map.player.direction);
+        let mut map: Map = ".#\n^.".parse().unwrap();
+        assert_eq!(Direction::Up, map.player.direction);
         map.turn_player();
-        assert_eq!(PlayerFacing::Right, map.player.direction);
+        assert_eq!(Direction::Right, map.player.direction);
         map.turn_player();
-        assert_eq!(PlayerFacing::Down, map.player.direction);
+        assert_eq!(Direction::Down, map.player.direction);
         map.turn_player();
-        assert_eq!(PlayerFacing::Left, map.player.direction);
+        assert_eq!(Direction::Left, map.player.direction);
         map.turn_player();
-        assert_eq!(PlayerFacing::Up, map.player.direction);
+        assert_eq!(Direction::Up, map.player.direction);
     }
 
     #[test]
     fn can_move_player() {
-        let lines = vec![".#", "^."];
         // . #
         // ^ .
-        let mut map = build_map(lines);
+        let mut map: Map = ".#\n^.".parse().unwrap();
         // move up once
         assert!(map.move_player());
-        assert_eq!(PlayerFacing::Up, map.player.direction);
-        assert_eq!(0, map.player.x);
-        assert_eq!(0, map.player.y);
+        assert_eq!(Direction::Up, map.player.direction);
+        assert_eq!(Point::new(0, 0), map.player.position);
         // we're out of bounds and didn't move.
         assert!(!map.move_player());
-        assert_eq!(0, map.player.x);
-        assert_eq!(0, map.player.y);
+        assert_eq!(Point::new(0, 0), map.player.position);
         // turn right
         map.turn_player();
-        assert_eq!(PlayerFacing::Right, map.player.direction);
+        assert_eq!(Direction::Right, map.player.direction);
         // move into obstacle, we'll be still at the same place, but have turned once more
         assert!(map.move_player());
-        assert_eq!(0, map.player.x);
-        assert_eq!(0, map.player.y);
-        assert_eq!(PlayerFacing::Down, map.player.direction);
+        assert_eq!(Point::new(0, 0), map.player.position);
+        assert_eq!(Direction::Down, map.player.direction);
         // 0,0 and 0,1 should've been visited
-        assert!(map.at(0, 0).visited);
-        assert!(map.at(0, 1).visited);
-        assert!(!map.at(1, 0).visited);
-        assert!(!map.at(1, 1).visited);
+        assert!(map.points[Point::new(0, 0)].visited);
+        assert!(map.points[Point::new(0, 1)].visited);
+        assert!(!map.points[Point::new(1, 0)].visited);
+        assert!(!map.points[Point::new(1, 1)].visited);
         assert_eq!(2, map.visited_count());
     }
+
+    const EXAMPLE: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    #[test]
+    fn can_walk_out_of_map() {
+        let mut map: Map = EXAMPLE.parse().unwrap();
+        assert_eq!(WalkOutcome::Exited, map.walk());
+        assert_eq!(41, map.visited_count());
+    }
+
+    #[test]
+    fn can_detect_loops() {
+        // . # . .
+        // . . . #
+        // # ^ . .
+        // . . # .
+        let mut map: Map = ".#..\n...#\n#^..\n..#.".parse().unwrap();
+        assert_eq!(WalkOutcome::Looped, map.walk());
+        assert_eq!(4, map.visited_count());
+    }
+
+    #[test]
+    fn can_add_obstacles() {
+        let mut map: Map = ".#\n^.".parse().unwrap();
+        assert!(map.add_obstacle(Point::new(1, 1)));
+        assert!(!map.add_obstacle(Point::new(1, 0)));
+        assert!(!map.add_obstacle(Point::new(0, 1)));
+        assert!(!map.add_obstacle(Point::new(2, 0)));
+        assert_eq!(CellContent::Obstacle, map.points[Point::new(1, 1)].content);
+    }
+
+    #[test]
+    fn can_find_loop_obstructions() {
+        let map: Map = EXAMPLE.parse().unwrap();
+        let mut obstructions = loop_obstructions(&map);
+        obstructions.sort();
+        assert_eq!(
+            vec![
+                Point::new(1, 8),
+                Point::new(3, 6),
+                Point::new(3, 8),
+                Point::new(6, 7),
+                Point::new(7, 7),
+                Point::new(7, 9),
+            ],
+            obstructions
+        );
+        assert_eq!(Some("6".to_string()), Dec06::part2(&map));
+    }
+
+    #[test]
+    fn can_jump_player() {
+        let mut map: Map = EXAMPLE.parse().unwrap();
+        assert!(map.jump_player());
+        assert_eq!(Point::new(4, 1), map.player.position);
+        assert_eq!(Direction::Right, map.player.direction);
+        assert!(map.jump_player());
+        assert_eq!(Point::new(8, 1), map.player.position);
+        assert_eq!(Direction::Down, map.player.direction);
+        map.reset_player();
+        assert_eq!(Point::new(4, 6), map.player.position);
+        assert_eq!(Direction::Up, map.player.direction);
+
+        // . # .
+        // . ^ .
+        let mut map: Map = ".#.\n.^.".parse().unwrap();
+        assert!(map.jump_player());
+        assert_eq!(Point::new(1, 1), map.player.position);
+        assert!(!map.jump_player());
+        assert_eq!(Point::new(1, 1), map.player.position);
+    }
+
+    #[test]
+    fn can_update_jump_table() {
+        let mut map: Map = EXAMPLE.parse().unwrap();
+        let obstacles = [
+            Point::new(3, 6),
+            Point::new(9, 9),
+            Point::new(4, 4),
+            Point::new(0, 0),
+        ];
+        for &obstacle in &obstacles {
+            assert!(map.add_obstacle(obstacle));
+            assert_eq!(JumpTable::new(&map.points), map.jumps);
+        }
+        for &obstacle in obstacles
+            .iter()
+            .chain(&[Point::new(4, 0), Point::new(9, 1)])
+        {
+            assert!(map.remove_obstacle(obstacle));
+            assert_eq!(JumpTable::new(&map.points), map.jumps);
+        }
+        assert!(!map.remove_obstacle(Point::new(4, 0)));
+    }
+
+    #[test]
+    fn can_jump_walk_like_walk() {
+        let map: Map = EXAMPLE.parse().unwrap();
+        for point in map.points.points() {
+            let mut map = map.clone();
+            map.add_obstacle(point);
+            assert_eq!(map.clone().walk(), map.jump_walk(), "obstacle at {}", point);
+        }
+    }
 }
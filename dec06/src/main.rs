@@ -1,14 +1,24 @@
 use std::fs;
 
-use dec06::{build_map, Map};
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec06::Dec06;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
+    let parsed = stats.measure("parse", || Dec06::parse(&input).or_exit("input.txt"));
+
+    let answer = stats.measure("part 1", || Dec06::part1(&parsed));
+    println!("part 1: {}", answer);
 
-    let mut map: Map = build_map(input.lines().collect());
-    let mut inside: bool = true;
-    while inside {
-        inside = map.move_player();
+    let answer = stats.measure("part 2", || Dec06::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("part 2: {}", answer);
     }
-    println!("part1: {}", map.visited_count());
+
+    stats.report();
 }
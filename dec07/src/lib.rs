@@ -1,3 +1,6 @@
+use std::str::FromStr;
+
+use aoc_core::{column_at, parse_field, parse_numbers, ParseError, Solution};
 use radix_fmt::*;
 
 #[derive(Debug)]
@@ -6,6 +9,20 @@ pub struct Equation {
     pub numbers: Vec<i64>,
 }
 
+impl FromStr for Equation {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Equation, ParseError> {
+        let colon = line
+            .find(':')
+            .ok_or_else(|| ParseError::unexpected(1, 1, line, "an equation like 190: 10 19"))?;
+        let result: i64 = parse_field(line[..colon].trim(), 1, 1, "a number")?;
+        let numbers: Vec<i64> = parse_numbers(&line[colon + 1..], 1)
+            .map_err(|e| e.shift(0, column_at(line, colon + 1) - 1))?;
+        Ok(Equation { result, numbers })
+    }
+}
+
 impl Equation {
     pub fn check(&self, concatenation: bool) -> bool {
         let operator_count = self.numbers.len() - 1;
@@ -30,7 +47,7 @@ impl Equation {
         false
     }
 
-    fn verify_operators(&self, operators: &Vec<Operator>) -> bool {
+    fn verify_operators(&self, operators: &[Operator]) -> bool {
         let mut result: i64 = self.numbers[0];
 
         for (i, operator) in operators.iter().enumerate() {
@@ -109,11 +126,56 @@ impl Iterator for OperatorSet {
     }
 }
 
+pub struct Dec07;
+
+fn calibration(equations: &[Equation], concatenation: bool) -> i64 {
+    equations
+        .iter()
+        .filter(|&e| e.check(concatenation))
+        .map(|e| e.result)
+        .sum()
+}
+
+impl Solution for Dec07 {
+    type Input = Vec<Equation>;
+
+    fn parse(input: &str) -> Result<Vec<Equation>, ParseError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| line.parse().map_err(|e: ParseError| e.shift(i, 0)))
+            .collect()
+    }
+
+    fn part1(equations: &Vec<Equation>) -> String {
+        calibration(equations, false).to_string()
+    }
+
+    fn part2(equations: &Vec<Equation>) -> Option<String> {
+        Some(calibration(equations, true).to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    #[test]
+    fn can_parse_equation() {
+        let equation: Equation = "3267: 81 40 27".parse().unwrap();
+        assert_eq!(3267, equation.result);
+        assert_eq!(vec![81, 40, 27], equation.numbers);
+        assert_eq!(
+            Err(ParseError::unexpected(1, 10, "4o", "a number")),
+            "3267: 81 4o 27".parse::<Equation>().map(|e| e.result)
+        );
+        assert_eq!(
+            Err(ParseError::missing(1, 6, "a number")),
+            "3267:".parse::<Equation>().map(|e| e.result)
+        );
+    }
+
     #[test]
     fn can_check_equation() {
         let bad_equation = Equation {
@@ -157,16 +219,16 @@ mod tests {
             result: 190,
             numbers: vec![10, 19],
         };
-        assert!(!equation.verify_operators(&vec![Operator::Addition]));
-        assert!(equation.verify_operators(&vec![Operator::Multiplication]));
+        assert!(!equation.verify_operators(&[Operator::Addition]));
+        assert!(equation.verify_operators(&[Operator::Multiplication]));
 
         let equation = Equation {
             result: 3267,
             numbers: vec![81, 40, 27],
         };
-        assert!(equation.verify_operators(&vec![Operator::Addition, Operator::Multiplication]));
-        assert!(equation.verify_operators(&vec![Operator::Multiplication, Operator::Addition]));
-        assert!(!equation.verify_operators(&vec![Operator::Addition, Operator::Addition]));
+        assert!(equation.verify_operators(&[Operator::Addition, Operator::Multiplication]));
+        assert!(equation.verify_operators(&[Operator::Multiplication, Operator::Addition]));
+        assert!(!equation.verify_operators(&[Operator::Addition, Operator::Addition]));
     }
 
     #[test]
@@ -175,13 +237,13 @@ mod tests {
             result: 156,
             numbers: vec![15, 6],
         };
-        assert!(equation.verify_operators(&vec![Operator::Concatenation]));
+        assert!(equation.verify_operators(&[Operator::Concatenation]));
 
         let equation = Equation {
             result: 7290,
             numbers: vec![6, 8, 6, 15],
         };
-        assert!(equation.verify_operators(&vec![
+        assert!(equation.verify_operators(&[
             Operator::Multiplication,
             Operator::Concatenation,
             Operator::Multiplication
@@ -191,7 +253,7 @@ mod tests {
             result: 192,
             numbers: vec![17, 8, 14],
         };
-        assert!(equation.verify_operators(&vec![Operator::Concatenation, Operator::Addition,]));
+        assert!(equation.verify_operators(&[Operator::Concatenation, Operator::Addition,]));
     }
 
     #[test]
@@ -243,4 +305,15 @@ mod tests {
             operator_set[26]
         );
     }
+
+    #[test]
+    fn can_parse_equations() {
+        let equations = Dec07::parse("190: 10 19\n3267: 81 40 27\n").unwrap();
+        assert_eq!(2, equations.len());
+        assert_eq!(3267, equations[1].result);
+        assert_eq!(
+            Err(ParseError::unexpected(2, 10, "4x", "a number")),
+            Dec07::parse("190: 10 19\n3267: 81 4x 27\n").map(|_| ())
+        );
+    }
 }
@@ -1,33 +1,24 @@
 use std::fs;
 
-use dec07::Equation;
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec07::Dec07;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let mut equations: Vec<Equation> = Vec::new();
+    let parsed = stats.measure("parse", || Dec07::parse(&input).or_exit("input.txt"));
 
-    for line in input.lines() {
-        let parts: Vec<&str> = line.split(':').collect();
-        let result: i64 = parts[0].trim().parse().expect("Failed to parse result");
-        let numbers: Vec<i64> = parts[1]
-            .split_whitespace()
-            .map(|n| n.trim().parse().expect("Failed to parse number"))
-            .collect();
+    let answer = stats.measure("part 1", || Dec07::part1(&parsed));
+    println!("pt1: {}", answer);
 
-        equations.push(Equation { result, numbers });
+    let answer = stats.measure("part 2", || Dec07::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("pt2: {}", answer);
     }
 
-    let valid_sum: i64 = equations
-        .iter()
-        .filter(|&e| e.check(false))
-        .map(|e| e.result)
-        .sum();
-    println!("pt1: {:?}", valid_sum);
-
-    let valid_sum: i64 = equations
-        .iter()
-        .filter(|&e| e.check(true))
-        .map(|e| e.result)
-        .sum();
-    println!("pt2: {:?}", valid_sum);
+    stats.report();
 }
@@ -1,43 +1,39 @@
 use std::collections::HashMap;
+use std::str::FromStr;
+
+use aoc_core::{ParseError, Solution};
+use aoc_grid::{Grid, Point};
 
 pub struct AntennaMap {
-    max_dimension: Point,
+    grid: Grid<char>,
     antennas: HashMap<char, Vec<Point>>,
 }
 
-impl AntennaMap {
-    pub fn new(lines: Vec<&str>) -> AntennaMap {
-        let max_dimension = Point {
-            x: lines[0].len() as i32 - 1,
-            y: lines.len() as i32 - 1,
-        };
+impl FromStr for AntennaMap {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<AntennaMap, ParseError> {
+        let grid: Grid<char> = input.parse()?;
 
         let mut antennas: HashMap<char, Vec<Point>> = HashMap::new();
-        for (y, line) in lines.iter().enumerate() {
-            for (x, ch) in line.chars().enumerate() {
-                match ch {
-                    '.' => (),
-                    _ => {
-                        let point = Point {
-                            x: x as i32,
-                            y: y as i32,
-                        };
-                        antennas.entry(ch).or_insert_with(Vec::new).push(point);
-                    }
-                };
-            }
+        for (point, &ch) in grid.iter() {
+            match ch {
+                '.' => (),
+                _ => {
+                    antennas.entry(ch).or_default().push(point);
+                }
+            };
         }
 
-        AntennaMap {
-            max_dimension,
-            antennas,
-        }
+        Ok(AntennaMap { grid, antennas })
     }
+}
 
+impl AntennaMap {
     pub fn antinodes(&self) -> Vec<Point> {
         let mut antinodes: Vec<Point> = Vec::new();
 
-        for (_ch, points) in &self.antennas {
+        for points in self.antennas.values() {
             for i in 0..(points.len() - 1) {
                 for j in i + 1..points.len() {
                     let (antinode1, antinode2) = Self::calculate_antinodes(&points[i], &points[j]);
@@ -52,36 +48,32 @@ impl AntennaMap {
         }
         antinodes
             .into_iter()
-            .filter(|an| {
-                an.x <= self.max_dimension.x
-                    && an.y <= self.max_dimension.y
-                    && an.x >= 0
-                    && an.y >= 0
-            })
+            .filter(|&an| self.grid.contains(an))
             .collect()
     }
 
     fn calculate_antinodes(one: &Point, other: &Point) -> (Point, Point) {
-        let x_diff = other.x - one.x;
-        let y_diff = other.y - one.y;
-
-        (
-            Point {
-                x: one.x - x_diff,
-                y: one.y - y_diff,
-            },
-            Point {
-                x: other.x + x_diff,
-                y: other.y + y_diff,
-            },
-        )
+        let diff = *other - *one;
+        (*one - diff, *other + diff)
     }
 }
 
-#[derive(PartialEq, Debug)]
-pub struct Point {
-    x: i32,
-    y: i32,
+pub struct Dec08;
+
+impl Solution for Dec08 {
+    type Input = AntennaMap;
+
+    fn parse(input: &str) -> Result<AntennaMap, ParseError> {
+        input.parse()
+    }
+
+    fn part1(map: &AntennaMap) -> String {
+        map.antinodes().len().to_string()
+    }
+
+    fn part2(_: &AntennaMap) -> Option<String> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -90,7 +82,7 @@ mod tests {
 
     #[test]
     fn can_scan_antenna_map() {
-        let lines = vec![
+        let lines = [
             "..........",
             "..........",
             "....a.....",
@@ -99,14 +91,14 @@ mod tests {
             "..........",
             "..........",
         ];
-        let map = AntennaMap::new(lines);
+        let map: AntennaMap = lines.join("\n").parse().unwrap();
         assert_eq!(1, map.antennas.len());
         assert_eq!(3, map.antennas.get(&'a').unwrap().len());
     }
 
     #[test]
     fn can_get_all_antinodes() {
-        let lines = vec![
+        let lines = [
             "..........",
             "..........",
             "....a.....",
@@ -115,7 +107,7 @@ mod tests {
             "..........",
             "..........",
         ];
-        let map = AntennaMap::new(lines);
+        let map: AntennaMap = lines.join("\n").parse().unwrap();
         let antinodes = map.antinodes();
         assert_eq!(4, antinodes.len());
         assert!(antinodes.contains(&Point { x: 0, y: 1 }));
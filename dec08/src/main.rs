@@ -1,10 +1,19 @@
 use std::fs;
 
-use dec08::AntennaMap;
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec08::Dec08;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let map = AntennaMap::new(input.lines().collect());
-    let antinodes = map.antinodes();
-    print!("pt1: {}", antinodes.len());
+    let parsed = stats.measure("parse", || Dec08::parse(&input).or_exit("input.txt"));
+
+    let answer = stats.measure("part 1", || Dec08::part1(&parsed));
+    println!("pt1: {}", answer);
+
+    stats.report();
 }
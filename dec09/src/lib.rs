@@ -1,14 +1,22 @@
-#[derive(Debug)]
+use std::str::FromStr;
+
+use aoc_core::{ParseError, Solution};
+
+#[derive(Debug, Clone)]
 pub struct Disk {
     blocks: Vec<Block>,
 }
 
-impl Disk {
-    pub fn new(disk_map: &str) -> Disk {
+impl FromStr for Disk {
+    type Err = ParseError;
+
+    fn from_str(disk_map: &str) -> Result<Disk, ParseError> {
         let mut blocks: Vec<Block> = Vec::new();
         let mut file_number: u32 = 0;
-        for (i, char) in disk_map.chars().enumerate() {
-            let count: u32 = char.to_digit(10).unwrap();
+        for (i, char) in disk_map.trim_end().chars().enumerate() {
+            let count: u32 = char
+                .to_digit(10)
+                .ok_or_else(|| ParseError::unexpected(1, i + 1, &char.to_string(), "a digit"))?;
             let block: Block = if i % 2 == 0 {
                 let content: Vec<u32> = vec![file_number; count as usize];
                 let block = Block {
@@ -29,9 +37,11 @@ impl Disk {
 
             blocks.push(block);
         }
-        Disk { blocks }
+        Ok(Disk { blocks })
     }
+}
 
+impl Disk {
     pub fn compact(&mut self) {
         let mut block_index_front: usize = 0;
         let mut block_index_back: usize = self.blocks.len() - 1;
@@ -116,20 +126,42 @@ impl Disk {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Block {
     size: u32,
     content: Vec<u32>,
     is_file: bool,
 }
 
+pub struct Dec09;
+
+impl Solution for Dec09 {
+    type Input = Disk;
+
+    fn parse(input: &str) -> Result<Disk, ParseError> {
+        input.parse()
+    }
+
+    fn part1(disk: &Disk) -> String {
+        let mut disk = disk.clone();
+        disk.compact();
+        disk.checksum().to_string()
+    }
+
+    fn part2(disk: &Disk) -> Option<String> {
+        let mut disk = disk.clone();
+        disk.compact_files();
+        Some(disk.checksum().to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn can_generate_blocks() {
-        let disk = Disk::new("12345");
+        let disk: Disk = "12345".parse().unwrap();
         assert_eq!(5, disk.blocks.len());
         assert_eq!(1, disk.blocks[0].size);
         assert_eq!(0, disk.blocks[0].content[0]);
@@ -143,9 +175,17 @@ mod tests {
         assert_eq!(2, disk.blocks[4].content[0]);
     }
 
+    #[test]
+    fn can_reject_bad_disk_map() {
+        assert_eq!(
+            Some(ParseError::unexpected(1, 3, "x", "a digit")),
+            "12x45".parse::<Disk>().err()
+        );
+    }
+
     #[test]
     fn can_do_simple_compact() {
-        let mut disk: Disk = Disk::new("234");
+        let mut disk: Disk = "234".parse().unwrap();
         disk.compact();
         assert_eq!(vec![0, 0], disk.blocks[0].content);
         assert_eq!(vec![1, 1, 1], disk.blocks[1].content);
@@ -154,7 +194,7 @@ mod tests {
 
     #[test]
     fn can_do_another_compact() {
-        let mut disk: Disk = Disk::new("12345");
+        let mut disk: Disk = "12345".parse().unwrap();
         disk.compact();
         assert_eq!(vec![0], disk.blocks[0].content);
         assert_eq!(vec![2, 2], disk.blocks[1].content);
@@ -165,7 +205,7 @@ mod tests {
 
     #[test]
     fn can_do_last_compact() {
-        let mut disk: Disk = Disk::new("2333133121414131402");
+        let mut disk: Disk = "2333133121414131402".parse().unwrap();
         disk.compact();
         assert_eq!(vec![0, 0], disk.blocks[0].content);
         assert_eq!(vec![9, 9, 8], disk.blocks[1].content);
@@ -185,7 +225,7 @@ mod tests {
 
     #[test]
     fn can_do_compact_by_files() {
-        let mut disk: Disk = Disk::new("2333133121414131402");
+        let mut disk: Disk = "2333133121414131402".parse().unwrap();
         disk.compact_files();
         assert_eq!(vec![0, 0], disk.blocks[0].content);
         assert_eq!(vec![9, 9, 2], disk.blocks[1].content);
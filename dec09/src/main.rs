@@ -1,16 +1,24 @@
 use std::fs;
 
-use dec09::Disk;
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec09::Dec09;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let normalized_input = input.replace('\n', "");
+    let parsed = stats.measure("parse", || Dec09::parse(&input).or_exit("input.txt"));
+
+    let answer = stats.measure("part 1", || Dec09::part1(&parsed));
+    println!("pt1: {}", answer);
 
-    let mut disk: Disk = Disk::new(&normalized_input);
-    disk.compact();
-    println!("pt1: {}", disk.checksum());
+    let answer = stats.measure("part 2", || Dec09::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("pt2: {}", answer);
+    }
 
-    let mut disk: Disk = Disk::new(&normalized_input);
-    disk.compact_files();
-    println!("pt1: {}", disk.checksum());
+    stats.report();
 }
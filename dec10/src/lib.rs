@@ -1,54 +1,35 @@
 use std::collections::HashSet;
+use std::str::FromStr;
 
-#[derive(Debug)]
-struct Trailhead {
-    x: usize,
-    y: usize,
-}
+use aoc_core::{ParseError, Solution};
+use aoc_grid::{Grid, Point};
 
 #[derive(Debug)]
 pub struct Map {
-    map: Vec<Vec<u32>>,
-    x_max: usize,
-    y_max: usize,
-    trailheads: Vec<Trailhead>,
+    map: Grid<u32>,
+    trailheads: Vec<Point>,
 }
 
-impl Map {
-    pub fn new(input: &String) -> Map {
-        let mut map: Vec<Vec<u32>> = Vec::new();
-        let mut trailheads: Vec<Trailhead> = Vec::new();
-
-        for (y, line) in input.lines().enumerate() {
-            let row: Vec<u32> = line
-                .chars()
-                .enumerate()
-                .map(|(x, c)| {
-                    let height = c.to_digit(10).unwrap();
-                    if height == 0 {
-                        trailheads.push(Trailhead { x, y });
-                    }
-
-                    height
-                })
-                .collect();
-            map.push(row);
-        }
-        let x_max = map[0].len() - 1;
-        let y_max = map.len() - 1;
-        Map {
-            map,
-            x_max,
-            y_max,
-            trailheads,
-        }
+impl FromStr for Map {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Map, ParseError> {
+        let map: Grid<u32> = Grid::parse(input, |c| c.to_digit(10))?;
+        let trailheads: Vec<Point> = map
+            .iter()
+            .filter(|(_, &height)| height == 0)
+            .map(|(point, _)| point)
+            .collect();
+        Ok(Map { map, trailheads })
     }
+}
 
+impl Map {
     pub fn score(&self) -> u32 {
         let trails = self.hike();
         let mut score = 0;
         for trail in trails {
-            let unique_peaks: HashSet<(usize, usize)> = trail.into_iter().collect();
+            let unique_peaks: HashSet<Point> = trail.into_iter().collect();
             score += unique_peaks.len();
         }
         score as u32
@@ -59,46 +40,48 @@ impl Map {
         trails.into_iter().map(|t| t.len() as u32).sum()
     }
 
-    fn hike(&self) -> Vec<Vec<(usize, usize)>> {
-        let mut trails: Vec<Vec<(usize, usize)>> = Vec::new();
+    fn hike(&self) -> Vec<Vec<Point>> {
+        let mut trails: Vec<Vec<Point>> = Vec::new();
 
-        for trailhead in &self.trailheads {
-            let mut found: Vec<(usize, usize)> = Vec::new();
-            self.wander(0, trailhead.x, trailhead.y, &mut found);
+        for &trailhead in &self.trailheads {
+            let mut found: Vec<Point> = Vec::new();
+            self.wander(0, trailhead, &mut found);
             trails.push(found);
         }
         trails
     }
 
-    fn wander(
-        &self,
-        current_step: u32,
-        current_x: usize,
-        current_y: usize,
-        found: &mut Vec<(usize, usize)>,
-    ) {
+    fn wander(&self, current_step: u32, current: Point, found: &mut Vec<Point>) {
         // if we're at 9, we're done.
         if current_step == 9 {
-            found.push((current_x, current_y));
+            found.push(current);
             return;
         }
 
         // check all neighbours for current_step +1
-        if current_y > 0 && self.map[current_y - 1][current_x] == current_step + 1 {
-            self.wander(current_step + 1, current_x, current_y - 1, found);
+        for (neighbour, &height) in self.map.neighbours4(current) {
+            if height == current_step + 1 {
+                self.wander(current_step + 1, neighbour, found);
+            }
         }
+    }
+}
 
-        if current_x < self.x_max && self.map[current_y][current_x + 1] == current_step + 1 {
-            self.wander(current_step + 1, current_x + 1, current_y, found);
-        }
+pub struct Dec10;
 
-        if current_y < self.y_max && self.map[current_y + 1][current_x] == current_step + 1 {
-            self.wander(current_step + 1, current_x, current_y + 1, found);
-        }
+impl Solution for Dec10 {
+    type Input = Map;
 
-        if current_x > 0 && self.map[current_y][current_x - 1] == current_step + 1 {
-            self.wander(current_step + 1, current_x - 1, current_y, found);
-        }
+    fn parse(input: &str) -> Result<Map, ParseError> {
+        input.parse()
+    }
+
+    fn part1(map: &Map) -> String {
+        map.score().to_string()
+    }
+
+    fn part2(map: &Map) -> Option<String> {
+        Some(map.rating().to_string())
     }
 }
 
@@ -111,12 +94,21 @@ mod tests {
         let input = "9870456
 8761328
 4562987";
-        let map = Map::new(&input.to_string());
+        let map: Map = input.parse().unwrap();
         assert_eq!(1, map.trailheads.len());
         assert_eq!(0, map.trailheads[0].y);
         assert_eq!(3, map.trailheads[0].x);
     }
 
+    #[test]
+    fn can_reject_bad_map() {
+        let map = "0123\n01.3".parse::<Map>();
+        assert_eq!(
+            Some(ParseError::unexpected(2, 3, ".", "a valid cell")),
+            map.err()
+        );
+    }
+
     #[test]
     fn can_hike() {
         let input = "9990999
@@ -127,7 +119,7 @@ mod tests {
 8111118
 9222229
 ";
-        let map = Map::new(&input.to_string());
+        let map: Map = input.parse().unwrap();
         assert_eq!(2, map.score());
     }
 
@@ -141,7 +133,7 @@ mod tests {
 8761111
 9871111
 ";
-        let map = Map::new(&input.to_string());
+        let map: Map = input.parse().unwrap();
         println!("{:?}", map);
         assert_eq!(4, map.score());
     }
@@ -156,7 +148,7 @@ mod tests {
 1119442
 5555501
 ";
-        let map = Map::new(&input.to_string());
+        let map: Map = input.parse().unwrap();
         println!("{:?}", map);
         assert_eq!(2, map.trailheads.len());
         assert_eq!(3, map.score());
@@ -172,7 +164,7 @@ mod tests {
 1187657
 1191111
 ";
-        let map = Map::new(&input.to_string());
+        let map: Map = input.parse().unwrap();
         println!("{:?}", map);
         assert_eq!(1, map.trailheads.len());
         assert_eq!(1, map.score());
@@ -188,7 +180,7 @@ mod tests {
 426789
 567892
 ";
-        let map = Map::new(&input.to_string());
+        let map: Map = input.parse().unwrap();
         println!("{:?}", map);
         assert_eq!(1, map.trailheads.len());
         assert_eq!(227, map.rating());
@@ -1,12 +1,24 @@
 use std::fs;
 
-use dec10::Map;
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec10::Dec10;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let map = Map::new(&input);
-    println!("pt1: {}", map.score());
+    let parsed = stats.measure("parse", || Dec10::parse(&input).or_exit("input.txt"));
+
+    let answer = stats.measure("part 1", || Dec10::part1(&parsed));
+    println!("pt1: {}", answer);
+
+    let answer = stats.measure("part 2", || Dec10::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("pt2: {}", answer);
+    }
 
-    let map = Map::new(&input);
-    println!("pt2: {}", map.rating());
+    stats.report();
 }
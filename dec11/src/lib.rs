@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use aoc_core::{parse_numbers, ParseError, Solution};
+
 pub fn blink(stones: Vec<i64>) -> Vec<i64> {
     let mut new_stones: Vec<i64> = Vec::new();
     for stone in stones {
@@ -70,6 +72,24 @@ pub fn blink_efficiently(initial_stones: &Vec<i64>, blink_count: i32) -> i64 {
     stones.values().sum()
 }
 
+pub struct Dec11;
+
+impl Solution for Dec11 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        parse_numbers(input.trim_end(), 1)
+    }
+
+    fn part1(stones: &Vec<i64>) -> String {
+        blink_efficiently(stones, 25).to_string()
+    }
+
+    fn part2(stones: &Vec<i64>) -> Option<String> {
+        Some(blink_efficiently(stones, 75).to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,4 +128,13 @@ mod tests {
         assert_eq!(9, blink_efficiently(&stones, 4));
         assert_eq!(22, blink_efficiently(&stones, 6));
     }
+
+    #[test]
+    fn can_parse_stones() {
+        assert_eq!(Ok(vec![125, 17]), Dec11::parse("125 17\n"));
+        assert_eq!(
+            Err(ParseError::unexpected(1, 5, "x", "a number")),
+            Dec11::parse("125 x\n")
+        );
+    }
 }
@@ -1,19 +1,24 @@
 use std::fs;
 
-use dec11::blink_efficiently;
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec11::Dec11;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let normalized_input = input.replace('\n', "");
+    let parsed = stats.measure("parse", || Dec11::parse(&input).or_exit("input.txt"));
 
-    let stones: Vec<i64> = normalized_input
-        .split(" ")
-        .map(|n| n.parse().unwrap())
-        .collect();
+    let answer = stats.measure("part 1", || Dec11::part1(&parsed));
+    println!("pt1: {}", answer);
 
-    let stone_count = blink_efficiently(&stones, 25);
-    println!("pt1: {:?}", &stone_count);
+    let answer = stats.measure("part 2", || Dec11::part2(&parsed));
+    if let Some(answer) = answer {
+        println!("pt2: {}", answer);
+    }
 
-    let stone_count = blink_efficiently(&stones, 75);
-    println!("pt2: {:?}", &stone_count);
+    stats.report();
 }
@@ -1,43 +1,48 @@
+use std::str::FromStr;
+
+use aoc_core::{blocks, column_at, parse_field, ParseError, Solution};
 use regex::Regex;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClawMachine {
     button_a: Button,
     button_b: Button,
     prize_location: Location,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Button {
     x_delta: i128,
     y_delta: i128,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Location {
     x: i128,
     y: i128,
 }
 
-pub fn build_machine(lines: Vec<&str>, corrected: bool) -> ClawMachine {
+impl ClawMachine {
+    // part 2 moves every prize 10000000000000 further away
+    pub fn corrected(&self) -> ClawMachine {
+        let mut machine = self.clone();
+        machine.prize_location.x += 10000000000000;
+        machine.prize_location.y += 10000000000000;
+        machine
+    }
+}
+
+pub fn build_machine(lines: Vec<&str>, corrected: bool) -> Result<ClawMachine, ParseError> {
     let re_a = Regex::new(r"Button A: X\+(?<x>\d+), Y\+(?<y>\d+)").unwrap();
-    let button_a_captures = re_a.captures(lines[0]).unwrap();
-    let button_a = Button {
-        x_delta: button_a_captures["x"].parse().unwrap(),
-        y_delta: button_a_captures["y"].parse().unwrap(),
-    };
+    let (x_delta, y_delta) = parse_line(&re_a, &lines, 0, "Button A: X+<x>, Y+<y>")?;
+    let button_a = Button { x_delta, y_delta };
 
     let re_b = Regex::new(r"Button B: X\+(?<x>\d+), Y\+(?<y>\d+)").unwrap();
-    let button_b_captures = re_b.captures(lines[1]).unwrap();
-    let button_b = Button {
-        x_delta: button_b_captures["x"].parse().unwrap(),
-        y_delta: button_b_captures["y"].parse().unwrap(),
-    };
+    let (x_delta, y_delta) = parse_line(&re_b, &lines, 1, "Button B: X+<x>, Y+<y>")?;
+    let button_b = Button { x_delta, y_delta };
 
     let re_p = Regex::new(r"Prize: X=(?<x>\d+), Y=(?<y>\d+)").unwrap();
-    let prize_captures = re_p.captures(lines[2]).unwrap();
-    let mut x_prize: i128 = prize_captures["x"].parse().unwrap();
-    let mut y_prize: i128 = prize_captures["y"].parse().unwrap();
+    let (mut x_prize, mut y_prize) = parse_line(&re_p, &lines, 2, "Prize: X=<x>, Y=<y>")?;
     if corrected {
         x_prize += 10000000000000;
         y_prize += 10000000000000;
@@ -48,10 +53,43 @@ pub fn build_machine(lines: Vec<&str>, corrected: bool) -> ClawMachine {
         y: y_prize,
     };
 
-    ClawMachine {
+    Ok(ClawMachine {
         button_a,
         button_b,
         prize_location,
+    })
+}
+
+fn parse_line(
+    re: &Regex,
+    lines: &[&str],
+    index: usize,
+    expected: &str,
+) -> Result<(i128, i128), ParseError> {
+    let line_number = index + 1;
+    let line = lines
+        .get(index)
+        .ok_or_else(|| ParseError::missing(line_number, 1, expected))?;
+    let captures = re
+        .captures(line)
+        .ok_or_else(|| ParseError::unexpected(line_number, 1, line, expected))?;
+    let number = |name: &str| {
+        let m = captures.name(name).unwrap();
+        parse_field(
+            m.as_str(),
+            line_number,
+            column_at(line, m.start()),
+            "a number",
+        )
+    };
+    Ok((number("x")?, number("y")?))
+}
+
+impl FromStr for ClawMachine {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<ClawMachine, ParseError> {
+        build_machine(input.lines().collect(), false)
     }
 }
 
@@ -102,6 +140,39 @@ pub fn calculate_price(press_a: i128, press_b: i128) -> i128 {
     press_a * 3 + press_b
 }
 
+pub struct Dec13;
+
+fn price(machines: &[ClawMachine]) -> i128 {
+    machines
+        .iter()
+        .filter_map(win_prize_efficiently)
+        .map(|(a, b)| calculate_price(a, b))
+        .sum()
+}
+
+impl Solution for Dec13 {
+    type Input = Vec<ClawMachine>;
+
+    fn parse(input: &str) -> Result<Vec<ClawMachine>, ParseError> {
+        blocks(input)
+            // a trailing blank line leaves an empty block behind
+            .filter(|(_, entry)| !entry.trim().is_empty())
+            .map(|(offset, entry)| {
+                build_machine(entry.lines().collect(), false).map_err(|e| e.shift(offset, 0))
+            })
+            .collect()
+    }
+
+    fn part1(machines: &Vec<ClawMachine>) -> String {
+        price(machines).to_string()
+    }
+
+    fn part2(machines: &Vec<ClawMachine>) -> Option<String> {
+        let corrected: Vec<ClawMachine> = machines.iter().map(|m| m.corrected()).collect();
+        Some(price(&corrected).to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,7 +184,7 @@ mod tests {
             "Button B: X+22, Y+67",
             "Prize: X=8400, Y=5400",
         ];
-        let machine = build_machine(lines, false);
+        let machine = build_machine(lines, false).unwrap();
         assert_eq!(94, machine.button_a.x_delta);
         assert_eq!(34, machine.button_a.y_delta);
         assert_eq!(22, machine.button_b.x_delta);
@@ -129,7 +200,7 @@ mod tests {
             "Button B: X+22, Y+67",
             "Prize: X=8400, Y=5400",
         ];
-        let machine = build_machine(lines, true);
+        let machine = build_machine(lines, true).unwrap();
         assert_eq!(94, machine.button_a.x_delta);
         assert_eq!(34, machine.button_a.y_delta);
         assert_eq!(22, machine.button_b.x_delta);
@@ -138,6 +209,30 @@ mod tests {
         assert_eq!(10000000005400, machine.prize_location.y);
     }
 
+    #[test]
+    fn can_reject_bad_machine() {
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=-5400";
+        assert_eq!(
+            Some(ParseError::unexpected(
+                3,
+                1,
+                "Prize: X=8400, Y=-5400",
+                "Prize: X=<x>, Y=<y>"
+            )),
+            input.parse::<ClawMachine>().err()
+        );
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y+67";
+        assert_eq!(
+            Some(ParseError::missing(3, 1, "Prize: X=<x>, Y=<y>")),
+            input.parse::<ClawMachine>().err()
+        );
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=999999999999999999999999999999999999999999, Y=1";
+        assert_eq!(
+            Some(10),
+            input.parse::<ClawMachine>().err().map(|e| e.column())
+        );
+    }
+
     #[test]
     fn can_abort_if_pointless_on_x() {
         let button_a = Button {
@@ -342,4 +437,25 @@ mod tests {
     fn can_calculate_price() {
         assert_eq!(280, calculate_price(80, 40));
     }
+
+    #[test]
+    fn can_parse_machines() {
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\nButton A: X+26, Y+66\nButton B: X+67, Y+21\nPrize: X=12748, Y=12176\n";
+        let machines = Dec13::parse(input).unwrap();
+        assert_eq!(2, machines.len());
+        assert_eq!(10000000012748, machines[1].corrected().prize_location.x);
+        assert_eq!(2, Dec13::parse(&format!("{}\n", input)).unwrap().len());
+        assert_eq!(2, Dec13::parse(&format!("{}\n \n", input)).unwrap().len());
+
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\nButton A: X+26, Y+66\nPrize: X=12748, Y=12176\n";
+        assert_eq!(
+            Err(ParseError::unexpected(
+                6,
+                1,
+                "Prize: X=12748, Y=12176",
+                "Button B: X+<x>, Y+<y>"
+            )),
+            Dec13::parse(input).map(|_| ())
+        );
+    }
 }
@@ -1,56 +1,33 @@
 use std::fs;
 
-use dec13::{build_machine, calculate_price, win_prize, win_prize_efficiently, ClawMachine};
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec13::{calculate_price, win_prize, Dec13};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let machine_input: Vec<&str> = input.split("\n\n").collect();
-
-    let machines: Vec<ClawMachine> = machine_input
-        .iter()
-        .map(|&entry| {
-            let machine_lines: Vec<&str> = entry.lines().collect();
-            build_machine(machine_lines, false)
-        })
-        .collect();
-
-    let price: i128 = machines
-        .iter()
-        .filter_map(win_prize_efficiently)
-        .map(|(a, b)| calculate_price(a, b))
-        .sum();
+    let machines = stats.measure("parse", || Dec13::parse(&input).or_exit("input.txt"));
 
+    let price = stats.measure("part 1", || Dec13::part1(&machines));
     println!("pt1-slow: {}", price);
 
-    let machines: Vec<ClawMachine> = machine_input
-        .iter()
-        .map(|&entry| {
-            let machine_lines: Vec<&str> = entry.lines().collect();
-            build_machine(machine_lines, false)
-        })
-        .collect();
-
-    let price: i128 = machines
-        .iter()
-        .filter_map(win_prize)
-        .map(|(a, b)| calculate_price(a, b))
-        .sum();
-
+    let price: i128 = stats.measure("part 1 (win_prize)", || {
+        machines
+            .iter()
+            .filter_map(win_prize)
+            .map(|(a, b)| calculate_price(a, b))
+            .sum()
+    });
     println!("pt1-fast: {}", price);
 
-    let machines: Vec<ClawMachine> = machine_input
-        .iter()
-        .map(|&entry| {
-            let machine_lines: Vec<&str> = entry.lines().collect();
-            build_machine(machine_lines, true)
-        })
-        .collect();
-
-    let price: i128 = machines
-        .iter()
-        .filter_map(win_prize_efficiently)
-        .map(|(a, b)| calculate_price(a, b))
-        .sum();
+    let price = stats.measure("part 2", || Dec13::part2(&machines));
+    if let Some(price) = price {
+        println!("pt2: {}", price);
+    }
 
-    println!("pt2: {}", price);
+    stats.report();
 }
@@ -1,80 +1,64 @@
+use std::str::FromStr;
+
+use aoc_core::{column_at, parse_field, ParseError, Solution};
+use aoc_grid::{Grid, Point};
 use regex::Regex;
 
 pub struct Map {
-    width: i32,
-    height: i32,
+    grid: Grid<char>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Robot {
-    x: i32,
-    y: i32,
-    x_speed: i32,
-    y_speed: i32,
+    position: Point,
+    velocity: Point,
 }
 
 impl Map {
     pub fn new(width: i32, height: i32) -> Map {
-        Map { width, height }
+        Map {
+            grid: Grid::new(width as usize, height as usize, ' '),
+        }
     }
 }
 
-impl Robot {
-    pub fn new(definition: &str) -> Robot {
+impl FromStr for Robot {
+    type Err = ParseError;
+
+    fn from_str(definition: &str) -> Result<Robot, ParseError> {
         let re = Regex::new(r"p=(?<x>\d+),(?<y>-?\d+)\s+v=(?<xs>-?\d+),(?<ys>-?\d+)").unwrap();
-        let captures = re.captures(definition).unwrap();
-        Robot {
-            x: captures["x"].parse().unwrap(),
-            y: captures["y"].parse().unwrap(),
-            x_speed: captures["xs"].parse().unwrap(),
-            y_speed: captures["ys"].parse().unwrap(),
-        }
+        let captures = re
+            .captures(definition)
+            .ok_or_else(|| ParseError::unexpected(1, 1, definition, "p=<x>,<y> v=<dx>,<dy>"))?;
+        let number = |name: &str| {
+            let m = captures.name(name).unwrap();
+            parse_field(m.as_str(), 1, column_at(definition, m.start()), "a number")
+        };
+        Ok(Robot {
+            position: Point::new(number("x")?, number("y")?),
+            velocity: Point::new(number("xs")?, number("ys")?),
+        })
     }
+}
 
+impl Robot {
     pub fn travel(&mut self, map: &Map) {
-        let mut new_x = self.x + self.x_speed;
-        if new_x < 0 {
-            new_x = map.width - new_x.abs();
-        }
-        if new_x > map.width - 1 {
-            new_x = (map.width - 1 - new_x).abs() - 1;
-        }
-        self.x = new_x;
-
-        let mut new_y = self.y + self.y_speed;
-        if new_y < 0 {
-            new_y = map.height - new_y.abs();
-        }
-        if new_y > map.height - 1 {
-            new_y = (map.height - 1 - new_y).abs() - 1;
-        }
-        self.y = new_y;
+        self.position = map.grid.wrap(self.position + self.velocity);
     }
 }
 
 pub fn count_by_quadrant(robots: &[Robot], map: &Map) -> (i32, i32, i32, i32) {
-    let edge_x = map.width / 2;
-    let edge_y = map.height / 2;
+    let edge_x = map.grid.width() as i32 / 2;
+    let edge_y = map.grid.height() as i32 / 2;
+    let positions = || robots.iter().map(|r| r.position);
 
-    let top_left_count = robots
-        .iter()
-        .filter(|r| r.x < edge_x && r.y < edge_y)
-        .count();
+    let top_left_count = positions().filter(|p| p.x < edge_x && p.y < edge_y).count();
 
-    let top_right_count = robots
-        .iter()
-        .filter(|r| r.x > edge_x && r.y < edge_y)
-        .count();
+    let top_right_count = positions().filter(|p| p.x > edge_x && p.y < edge_y).count();
 
-    let bottom_left_count = robots
-        .iter()
-        .filter(|r| r.x < edge_x && r.y > edge_y)
-        .count();
+    let bottom_left_count = positions().filter(|p| p.x < edge_x && p.y > edge_y).count();
 
-    let bottom_right_count = robots
-        .iter()
-        .filter(|r| r.x > edge_x && r.y > edge_y)
-        .count();
+    let bottom_right_count = positions().filter(|p| p.x > edge_x && p.y > edge_y).count();
 
     (
         top_left_count as i32,
@@ -84,123 +68,145 @@ pub fn count_by_quadrant(robots: &[Robot], map: &Map) -> (i32, i32, i32, i32) {
     )
 }
 
-pub fn robot_display(robots: &[Robot], map: &Map) -> Vec<Vec<char>> {
-    let mut output: Vec<Vec<char>> = vec![vec![' '; map.width as usize]; map.height as usize];
+pub fn robot_display(robots: &[Robot], map: &Map) -> Grid<char> {
+    let mut output: Grid<char> = map.grid.clone();
 
-    for (x, y) in robots.iter().map(|r| (r.x, r.y)) {
-        output[y as usize][x as usize] = '*';
+    for robot in robots {
+        output.set(robot.position, '*');
     }
 
     output
 }
 
+pub struct Dec14;
+
+impl Solution for Dec14 {
+    type Input = Vec<Robot>;
+
+    fn parse(input: &str) -> Result<Vec<Robot>, ParseError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| line.parse().map_err(|e: ParseError| e.shift(i, 0)))
+            .collect()
+    }
+
+    fn part1(robots: &Vec<Robot>) -> String {
+        let map = Map::new(101, 103);
+        let mut robots = robots.clone();
+        for _ in 0..100 {
+            for robot in &mut robots {
+                robot.travel(&map);
+            }
+        }
+        let (tl, tr, bl, br) = count_by_quadrant(&robots, &map);
+        (tl * tr * bl * br).to_string()
+    }
+
+    // finding the christmas tree needs a pair of eyes, see `cargo run -p dec14`.
+    fn part2(_: &Vec<Robot>) -> Option<String> {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn can_make_robot() {
-        let robot = Robot::new("p=0,4 v=3,-3");
-        assert_eq!(0, robot.x);
-        assert_eq!(4, robot.y);
-        assert_eq!(3, robot.x_speed);
-        assert_eq!(-3, robot.y_speed);
+        let robot: Robot = "p=0,4 v=3,-3".parse().unwrap();
+        assert_eq!(0, robot.position.x);
+        assert_eq!(4, robot.position.y);
+        assert_eq!(3, robot.velocity.x);
+        assert_eq!(-3, robot.velocity.y);
+    }
+
+    #[test]
+    fn can_reject_bad_robot() {
+        assert_eq!(
+            Some(ParseError::unexpected(
+                1,
+                1,
+                "p=0,4 v=3",
+                "p=<x>,<y> v=<dx>,<dy>"
+            )),
+            "p=0,4 v=3".parse::<Robot>().err()
+        );
+        assert_eq!(
+            Some(ParseError::unexpected(1, 5, "99999999999", "a number")),
+            "p=0,99999999999 v=3,-3".parse::<Robot>().err()
+        );
     }
 
     #[test]
     fn can_make_robot_travel() {
         let mut robot = Robot {
-            x: 2,
-            y: 4,
-            x_speed: 2,
-            y_speed: -3,
-        };
-        let map = Map {
-            width: 11,
-            height: 7,
+            position: Point::new(2, 4),
+            velocity: Point::new(2, -3),
         };
+        let map = Map::new(11, 7);
         // second 1
         robot.travel(&map);
-        assert_eq!(4, robot.x);
-        assert_eq!(1, robot.y);
+        assert_eq!(4, robot.position.x);
+        assert_eq!(1, robot.position.y);
         // second 2
         robot.travel(&map);
-        assert_eq!(6, robot.x);
-        assert_eq!(5, robot.y);
+        assert_eq!(6, robot.position.x);
+        assert_eq!(5, robot.position.y);
         // second 3
         robot.travel(&map);
-        assert_eq!(8, robot.x);
-        assert_eq!(2, robot.y);
+        assert_eq!(8, robot.position.x);
+        assert_eq!(2, robot.position.y);
         // second 4
         robot.travel(&map);
-        assert_eq!(10, robot.x);
-        assert_eq!(6, robot.y);
+        assert_eq!(10, robot.position.x);
+        assert_eq!(6, robot.position.y);
         // second 5
         robot.travel(&map);
-        assert_eq!(1, robot.x);
-        assert_eq!(3, robot.y);
+        assert_eq!(1, robot.position.x);
+        assert_eq!(3, robot.position.y);
     }
 
     #[test]
     fn can_count_robots() {
-        let map = Map {
-            width: 11,
-            height: 7,
-        };
+        let map = Map::new(11, 7);
         let robots: Vec<Robot> = vec![
             Robot {
-                x: 6,
-                y: 0,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(6, 0),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 6,
-                y: 0,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(6, 0),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 9,
-                y: 0,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(9, 0),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 0,
-                y: 2,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(0, 2),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 3,
-                y: 5,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(3, 5),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 4,
-                y: 5,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(4, 5),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 4,
-                y: 5,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(4, 5),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 1,
-                y: 6,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(1, 6),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 6,
-                y: 6,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(6, 6),
+                velocity: Point::new(0, 0),
             },
         ];
 
@@ -217,37 +223,44 @@ mod tests {
         let map: Map = Map::new(3, 4);
         let robots: Vec<Robot> = vec![
             Robot {
-                x: 0,
-                y: 0,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(0, 0),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 1,
-                y: 1,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(1, 1),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 2,
-                y: 2,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(2, 2),
+                velocity: Point::new(0, 0),
             },
             Robot {
-                x: 0,
-                y: 3,
-                x_speed: 0,
-                y_speed: 0,
+                position: Point::new(0, 3),
+                velocity: Point::new(0, 0),
             },
         ];
 
-        let display: Vec<Vec<char>> = robot_display(&robots, &map);
-        assert_eq!(vec!['*', ' ', ' '], display[0]);
-        assert_eq!(vec![' ', '*', ' '], display[1]);
-        assert_eq!(vec![' ', ' ', '*'], display[2]);
-        assert_eq!(vec!['*', ' ', ' '], display[3]);
+        let display: Grid<char> = robot_display(&robots, &map);
+        assert_eq!(&['*', ' ', ' '], display.row(0));
+        assert_eq!(&[' ', '*', ' '], display.row(1));
+        assert_eq!(&[' ', ' ', '*'], display.row(2));
+        assert_eq!(&['*', ' ', ' '], display.row(3));
 
-        println!("{:#?}", display);
+        println!("{}", display);
+    }
+
+    #[test]
+    fn can_parse_robots() {
+        let robots = Dec14::parse("p=0,4 v=3,-3\np=6,3 v=-1,-3\n").unwrap();
+        assert_eq!(Point::new(6, 3), robots[1].position);
+        assert_eq!(
+            Err(ParseError::unexpected(
+                2,
+                1,
+                "p=6,3",
+                "p=<x>,<y> v=<dx>,<dy>"
+            )),
+            Dec14::parse("p=0,4 v=3,-3\np=6,3\n").map(|_| ())
+        );
     }
 }
@@ -1,26 +1,35 @@
 use std::fs;
 use std::io::{stdin, Read};
 
-use dec14::{count_by_quadrant, robot_display, Map, Robot};
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use aoc_grid::Grid;
+use dec14::{robot_display, Dec14, Map, Robot};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let mut robots: Vec<Robot> = input.lines().map(Robot::new).collect();
+    let mut robots: Vec<Robot> =
+        stats.measure("parse", || Dec14::parse(&input).or_exit("input.txt"));
     //let map_input1 = Map::new(11, 7);
     let map_input = Map::new(101, 103);
 
+    let safety_factor = stats.measure("part 1", || Dec14::part1(&robots));
+    println!("-------");
+    println!("| pt1: {}", safety_factor);
+    println!("-------");
+
+    // part 2 waits for someone to spot the christmas tree, there's nothing to measure there
+    stats.report();
+
     for i in 0..200000 {
         for robot in &mut robots {
             robot.travel(&map_input);
         }
 
-        if i == 99 {
-            let (tl, tr, bl, br) = count_by_quadrant(&robots, &map_input);
-            println!("-------");
-            println!("| pt1: {}", tl * tr * bl * br);
-            println!("-------");
-        }
-
         let display = &robot_display(&robots, &map_input);
         let empty_lines = empty_line_count(display);
         if empty_lines > 5 {
@@ -31,18 +40,13 @@ fn main() {
     }
 }
 
-fn print_display(display: &Vec<Vec<char>>) {
-    for row in display {
-        for &ch in row {
-            print!("{}", ch);
-        }
-        println!(); // Move to the next eline after printing each row
-    }
+fn print_display(display: &Grid<char>) {
+    println!("{}", display);
 }
 
-fn empty_line_count(display: &Vec<Vec<char>>) -> u32 {
+fn empty_line_count(display: &Grid<char>) -> u32 {
     display
-        .iter()
+        .rows()
         .filter(|row| row.iter().all(|&ch| ch == ' '))
         .count() as u32
 }
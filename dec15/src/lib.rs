@@ -1,164 +1,71 @@
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug)]
+use aoc_core::{blocks, ParseError, Solution};
+use aoc_grid::{Direction, Grid, Point};
+
+#[derive(Debug, Clone)]
 pub struct Warehouse {
-    tiles: Vec<Vec<Tile>>,
-    robot_position: (usize, usize),
+    tiles: Grid<Tile>,
+    robot_position: Point,
 }
 
-impl Warehouse {
-    pub fn new(lines: Vec<&str>) -> Warehouse {
-        let mut tiles: Vec<Vec<Tile>> = Vec::new();
-        let mut robot_position: Option<(usize, usize)> = None;
-
-        for (y, line) in lines.iter().enumerate() {
-            let mut row: Vec<Tile> = Vec::new();
-            for (x, ch) in line.chars().enumerate() {
-                let on_tile: OnTile = match ch {
-                    'O' => OnTile::Box,
-                    '.' => OnTile::Empty,
-                    '@' => OnTile::Robot {},
-                    '#' => OnTile::Wall,
-                    _ => panic!(),
-                };
-                if on_tile == OnTile::Robot {
-                    robot_position = Some((x, y));
-                }
-                row.push(Tile { on_tile });
-            }
-            tiles.push(row);
-        }
-        let robot_position = robot_position.expect("No robot found!");
-
-        Warehouse {
+impl FromStr for Warehouse {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Warehouse, ParseError> {
+        let tiles: Grid<Tile> = Grid::parse(input, |ch| {
+            let on_tile: OnTile = match ch {
+                'O' => OnTile::Box,
+                '.' => OnTile::Empty,
+                '@' => OnTile::Robot {},
+                '#' => OnTile::Wall,
+                _ => return None,
+            };
+            Some(Tile { on_tile })
+        })?;
+        let robot_position = tiles
+            .position(|t| t.on_tile == OnTile::Robot)
+            .ok_or_else(|| ParseError::missing(tiles.height() + 1, 1, "a robot '@'"))?;
+
+        Ok(Warehouse {
             tiles,
             robot_position,
-        }
+        })
     }
+}
 
+impl Warehouse {
     pub fn move_robot(&mut self, movement: &Movement) {
-        match movement.direction {
-            MovementDirection::Up => {
-                let x = self.robot_position.0;
-                let mut y = self.robot_position.1 - 1;
-                let empty_space: Option<(usize, usize)>;
-                loop {
-                    if self.tiles[y][x].is_wall() {
-                        return;
-                    }
-                    if self.tiles[y][x].is_empty() {
-                        empty_space = Some((x, y));
-                        break;
-                    }
-                    y -= 1;
-                }
-
-                let mut current_y = empty_space.expect("weird. no empty space, no wall.").1;
-                let mut next_y = current_y + 1;
-                while next_y < self.robot_position.1 {
-                    let moved_from_tile = self.tiles[next_y][x].on_tile;
-                    self.tiles[current_y][x].place(moved_from_tile);
-                    self.tiles[next_y][x].place(OnTile::Empty);
-                    current_y += 1;
-                    next_y += 1;
-                }
-                self.tiles[current_y][x].place(OnTile::Robot);
-                self.tiles[next_y][x].place(OnTile::Empty);
-                self.robot_position = (x, current_y);
+        let direction = movement.direction;
+        let mut position = self.robot_position.step(direction);
+        let empty_space: Point;
+        loop {
+            if self.tiles[position].is_wall() {
+                return;
             }
-            MovementDirection::Right => {
-                let mut x = self.robot_position.0 + 1;
-                let y = self.robot_position.1;
-                let empty_space: Option<(usize, usize)>;
-                loop {
-                    if self.tiles[y][x].is_wall() {
-                        return;
-                    }
-                    if self.tiles[y][x].is_empty() {
-                        empty_space = Some((x, y));
-                        break;
-                    }
-                    x += 1;
-                }
-
-                let mut current_x = empty_space.expect("weird. no empty space, no wall.").0;
-                let mut next_x = current_x - 1;
-                while next_x > self.robot_position.0 {
-                    let moved_from_tile = self.tiles[y][next_x].on_tile;
-                    self.tiles[y][current_x].place(moved_from_tile);
-                    self.tiles[y][next_x].place(OnTile::Empty);
-                    current_x -= 1;
-                    next_x -= 1;
-                }
-                self.tiles[y][current_x].place(OnTile::Robot);
-                self.tiles[y][next_x].place(OnTile::Empty);
-                self.robot_position = (current_x, y);
-            }
-            MovementDirection::Down => {
-                let x = self.robot_position.0;
-                let mut y = self.robot_position.1 + 1;
-                let empty_space: Option<(usize, usize)>;
-                loop {
-                    if self.tiles[y][x].is_wall() {
-                        return;
-                    }
-                    if self.tiles[y][x].is_empty() {
-                        empty_space = Some((x, y));
-                        break;
-                    }
-                    y += 1;
-                }
-
-                let mut current_y = empty_space.expect("weird. no empty space, no wall.").1;
-                let mut next_y = current_y - 1;
-                while next_y > self.robot_position.1 {
-                    let moved_from_tile = self.tiles[next_y][x].on_tile;
-                    self.tiles[current_y][x].place(moved_from_tile);
-                    self.tiles[next_y][x].place(OnTile::Empty);
-                    current_y -= 1;
-                    next_y -= 1;
-                }
-                self.tiles[current_y][x].place(OnTile::Robot);
-                self.tiles[next_y][x].place(OnTile::Empty);
-                self.robot_position = (x, current_y);
-            }
-            MovementDirection::Left => {
-                let mut x = self.robot_position.0 - 1;
-                let y = self.robot_position.1;
-                let empty_space: Option<(usize, usize)>;
-                loop {
-                    if self.tiles[y][x].is_wall() {
-                        return;
-                    }
-                    if self.tiles[y][x].is_empty() {
-                        empty_space = Some((x, y));
-                        break;
-                    }
-                    x -= 1;
-                }
-
-                let mut current_x = empty_space.expect("weird. no empty space, no wall.").0;
-                let mut next_x = current_x + 1;
-                while next_x < self.robot_position.0 {
-                    let moved_from_tile = self.tiles[y][next_x].on_tile;
-                    self.tiles[y][current_x].place(moved_from_tile);
-                    self.tiles[y][next_x].place(OnTile::Empty);
-                    current_x += 1;
-                    next_x += 1;
-                }
-                self.tiles[y][current_x].place(OnTile::Robot);
-                self.tiles[y][next_x].place(OnTile::Empty);
-                self.robot_position = (current_x, y);
+            if self.tiles[position].is_empty() {
+                empty_space = position;
+                break;
             }
+            position = position.step(direction);
+        }
+
+        // everything between the robot and the empty space is a box, so shifting the whole row
+        // by one is the same as moving the first box to the end of it.
+        let next_position = self.robot_position.step(direction);
+        if empty_space != next_position {
+            self.tiles[empty_space].place(OnTile::Box);
         }
+        self.tiles[next_position].place(OnTile::Robot);
+        self.tiles[self.robot_position].place(OnTile::Empty);
+        self.robot_position = next_position;
     }
 
     pub fn gps_sum(&self) -> u32 {
         let mut sum = 0;
-        for (y, row) in self.tiles.iter().enumerate() {
-            for (x, _tile) in row.iter().enumerate().filter(|(_, t)| t.is_box()) {
-                sum += 100 * y + x;
-            }
+        for (point, _tile) in self.tiles.iter().filter(|(_, t)| t.is_box()) {
+            sum += 100 * point.y + point.x;
         }
         sum as u32
     }
@@ -166,21 +73,11 @@ impl Warehouse {
 
 impl fmt::Display for Warehouse {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut output: String = "".to_string();
-
-        for tile_row in &self.tiles {
-            let mut row_output: String = "".to_string();
-            for tile in tile_row {
-                row_output = format!("{}{}", row_output, tile.on_tile);
-            }
-            output = format!("{}\n{}", output, row_output);
-        }
-
-        write!(f, "{}", output)
+        write!(f, "\n{}", self.tiles.map(|tile| tile.on_tile))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Tile {
     on_tile: OnTile,
 }
@@ -198,10 +95,6 @@ impl Tile {
         matches!(self.on_tile, OnTile::Wall)
     }
 
-    fn is_robot(&self) -> bool {
-        matches!(self.on_tile, OnTile::Robot)
-    }
-
     fn place(&mut self, on_tile: OnTile) {
         self.on_tile = on_tile;
     }
@@ -229,28 +122,60 @@ impl fmt::Display for OnTile {
 
 #[derive(Debug)]
 pub struct Movement {
-    direction: MovementDirection,
+    direction: Direction,
 }
 
-impl Movement {
-    pub fn new(ch: char) -> Movement {
-        let direction: MovementDirection = match ch {
-            '^' => MovementDirection::Up,
-            '>' => MovementDirection::Right,
-            'v' => MovementDirection::Down,
-            '<' => MovementDirection::Left,
-            _ => panic!(),
+impl TryFrom<char> for Movement {
+    type Error = ParseError;
+
+    fn try_from(ch: char) -> Result<Movement, ParseError> {
+        let direction: Direction = match ch {
+            '^' => Direction::Up,
+            '>' => Direction::Right,
+            'v' => Direction::Down,
+            '<' => Direction::Left,
+            _ => return Err(ParseError::unexpected(1, 1, &ch.to_string(), "one of ^>v<")),
         };
-        Movement { direction }
+        Ok(Movement { direction })
     }
 }
 
-#[derive(PartialEq, Debug)]
-enum MovementDirection {
-    Up,
-    Right,
-    Down,
-    Left,
+pub struct Dec15;
+
+impl Solution for Dec15 {
+    type Input = (Warehouse, Vec<Movement>);
+
+    fn parse(input: &str) -> Result<(Warehouse, Vec<Movement>), ParseError> {
+        let input_parts: Vec<(usize, &str)> = blocks(input).collect();
+        if input_parts.len() != 2 {
+            let line = input.lines().count() + 1;
+            return Err(ParseError::missing(line, 1, "a list of movements"));
+        }
+        let (_, warehouse_input) = input_parts[0];
+        let (movements_offset, movements_input) = input_parts[1];
+
+        let warehouse: Warehouse = warehouse_input.parse()?;
+        let mut movements: Vec<Movement> = Vec::new();
+        for (y, line) in movements_input.lines().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                let movement = Movement::try_from(ch);
+                movements.push(movement.map_err(|e| e.shift(movements_offset + y, x))?);
+            }
+        }
+        Ok((warehouse, movements))
+    }
+
+    fn part1((warehouse, movements): &(Warehouse, Vec<Movement>)) -> String {
+        let mut warehouse = warehouse.clone();
+        for movement in movements {
+            warehouse.move_robot(movement);
+        }
+        warehouse.gps_sum().to_string()
+    }
+
+    fn part2(_: &(Warehouse, Vec<Movement>)) -> Option<String> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -259,23 +184,23 @@ mod tests {
 
     #[test]
     fn can_create_warehouse() {
-        let lines = vec!["######", "##@O.#"];
-        let warehouse = Warehouse::new(lines);
-        assert_eq!((2, 1), warehouse.robot_position);
-
-        assert_eq!(OnTile::Wall, warehouse.tiles[0][0].on_tile);
-        assert_eq!(OnTile::Wall, warehouse.tiles[0][1].on_tile);
-        assert_eq!(OnTile::Wall, warehouse.tiles[0][2].on_tile);
-        assert_eq!(OnTile::Wall, warehouse.tiles[0][3].on_tile);
-        assert_eq!(OnTile::Wall, warehouse.tiles[0][4].on_tile);
-        assert_eq!(OnTile::Wall, warehouse.tiles[0][5].on_tile);
-
-        assert_eq!(OnTile::Wall, warehouse.tiles[1][0].on_tile);
-        assert_eq!(OnTile::Wall, warehouse.tiles[1][1].on_tile);
-        assert_eq!(OnTile::Robot, warehouse.tiles[1][2].on_tile);
-        assert_eq!(OnTile::Box, warehouse.tiles[1][3].on_tile);
-        assert_eq!(OnTile::Empty, warehouse.tiles[1][4].on_tile);
-        assert_eq!(OnTile::Wall, warehouse.tiles[1][5].on_tile);
+        let lines = ["######", "##@O.#"];
+        let warehouse: Warehouse = lines.join("\n").parse().unwrap();
+        assert_eq!(Point::new(2, 1), warehouse.robot_position);
+
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(0, 0)].on_tile);
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(1, 0)].on_tile);
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(2, 0)].on_tile);
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(3, 0)].on_tile);
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(4, 0)].on_tile);
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(5, 0)].on_tile);
+
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(0, 1)].on_tile);
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(1, 1)].on_tile);
+        assert_eq!(OnTile::Robot, warehouse.tiles[Point::new(2, 1)].on_tile);
+        assert_eq!(OnTile::Box, warehouse.tiles[Point::new(3, 1)].on_tile);
+        assert_eq!(OnTile::Empty, warehouse.tiles[Point::new(4, 1)].on_tile);
+        assert_eq!(OnTile::Wall, warehouse.tiles[Point::new(5, 1)].on_tile);
     }
 
     #[test]
@@ -283,25 +208,41 @@ mod tests {
         let mut movements: Vec<Movement> = Vec::new();
         let input = "<vv>^";
         for ch in input.chars() {
-            movements.push(Movement::new(ch));
+            movements.push(Movement::try_from(ch).unwrap());
         }
-        assert_eq!(MovementDirection::Left, movements[0].direction);
-        assert_eq!(MovementDirection::Down, movements[1].direction);
-        assert_eq!(MovementDirection::Down, movements[2].direction);
-        assert_eq!(MovementDirection::Right, movements[3].direction);
-        assert_eq!(MovementDirection::Up, movements[4].direction);
+        assert_eq!(Direction::Left, movements[0].direction);
+        assert_eq!(Direction::Down, movements[1].direction);
+        assert_eq!(Direction::Down, movements[2].direction);
+        assert_eq!(Direction::Right, movements[3].direction);
+        assert_eq!(Direction::Up, movements[4].direction);
+    }
+
+    #[test]
+    fn can_reject_bad_input() {
+        assert_eq!(
+            Some(ParseError::unexpected(1, 1, "x", "one of ^>v<")),
+            Movement::try_from('x').err()
+        );
+        assert_eq!(
+            Some(ParseError::unexpected(2, 4, "o", "a valid cell")),
+            "######\n##@o.#".parse::<Warehouse>().err()
+        );
+        assert_eq!(
+            Some(ParseError::missing(3, 1, "a robot '@'")),
+            "######\n##.O.#".parse::<Warehouse>().err()
+        );
     }
 
     #[test]
     fn can_bump_into_wall() {
         // ######
         // ##@O.#
-        let lines = vec!["######", "##@O.#"];
-        let mut warehouse = Warehouse::new(lines);
-        let move_up = Movement::new('^');
+        let lines = ["######", "##@O.#"];
+        let mut warehouse: Warehouse = lines.join("\n").parse().unwrap();
+        let move_up = Movement::try_from('^').unwrap();
         warehouse.move_robot(&move_up);
-        assert_eq!(2, warehouse.robot_position.0); // x
-        assert_eq!(1, warehouse.robot_position.1); // y
+        assert_eq!(2, warehouse.robot_position.x);
+        assert_eq!(1, warehouse.robot_position.y);
     }
 
     #[test]
@@ -312,31 +253,31 @@ mod tests {
         // #O.#
         // #..#
         // #@.#
-        let lines = vec!["####", "#..#", "#O.#", "#O.#", "#..#", "#@.#"];
-        let mut warehouse = Warehouse::new(lines);
-        let move_up = Movement::new('^');
+        let lines = ["####", "#..#", "#O.#", "#O.#", "#..#", "#@.#"];
+        let mut warehouse: Warehouse = lines.join("\n").parse().unwrap();
+        let move_up = Movement::try_from('^').unwrap();
         // move up once
         warehouse.move_robot(&move_up);
-        assert_eq!(1, warehouse.robot_position.0); // x
-        assert_eq!(4, warehouse.robot_position.1); // y
+        assert_eq!(1, warehouse.robot_position.x);
+        assert_eq!(4, warehouse.robot_position.y);
 
         // one more time, this time also move boxes
         warehouse.move_robot(&move_up);
-        assert_eq!(1, warehouse.robot_position.0); // x
-        assert_eq!(3, warehouse.robot_position.1); // y
-        assert!(warehouse.tiles[0][1].is_wall());
-        assert!(warehouse.tiles[1][1].is_box());
-        assert!(warehouse.tiles[2][1].is_box());
-        assert!(warehouse.tiles[3][1].is_robot());
+        assert_eq!(1, warehouse.robot_position.x);
+        assert_eq!(3, warehouse.robot_position.y);
+        assert!(warehouse.tiles[Point::new(1, 0)].is_wall());
+        assert!(warehouse.tiles[Point::new(1, 1)].is_box());
+        assert!(warehouse.tiles[Point::new(1, 2)].is_box());
+        assert_eq!(OnTile::Robot, warehouse.tiles[Point::new(1, 3)].on_tile);
 
         // third time, nothing will happen
         warehouse.move_robot(&move_up);
-        assert_eq!(1, warehouse.robot_position.0); // x
-        assert_eq!(3, warehouse.robot_position.1); // y
-        assert!(warehouse.tiles[0][1].is_wall());
-        assert!(warehouse.tiles[1][1].is_box());
-        assert!(warehouse.tiles[2][1].is_box());
-        assert!(warehouse.tiles[3][1].is_robot());
+        assert_eq!(1, warehouse.robot_position.x);
+        assert_eq!(3, warehouse.robot_position.y);
+        assert!(warehouse.tiles[Point::new(1, 0)].is_wall());
+        assert!(warehouse.tiles[Point::new(1, 1)].is_box());
+        assert!(warehouse.tiles[Point::new(1, 2)].is_box());
+        assert_eq!(OnTile::Robot, warehouse.tiles[Point::new(1, 3)].on_tile);
     }
 
     #[test]
@@ -344,8 +285,23 @@ mod tests {
         // #######
         // #...O..
         // #@.....
-        let lines = vec!["#######", "#...O..", "#@....."];
-        let warehouse = Warehouse::new(lines);
+        let lines = ["#######", "#...O..", "#@....."];
+        let warehouse: Warehouse = lines.join("\n").parse().unwrap();
         assert_eq!(104, warehouse.gps_sum());
     }
+
+    #[test]
+    fn can_parse_warehouse_and_movements() {
+        let (warehouse, movements) = Dec15::parse("####\n#@O#\n####\n\n<>\n^v\n").unwrap();
+        assert_eq!(Point::new(1, 1), warehouse.robot_position);
+        assert_eq!(4, movements.len());
+        assert_eq!(
+            Err(ParseError::unexpected(6, 2, "x", "one of ^>v<")),
+            Dec15::parse("####\n#@O#\n####\n\n<>\n^x\n").map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::missing(4, 1, "a list of movements")),
+            Dec15::parse("####\n#@O#\n####\n").map(|_| ())
+        );
+    }
 }
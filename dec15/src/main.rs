@@ -1,23 +1,19 @@
 use std::fs;
 
-use dec15::{Movement, Warehouse};
+use aoc_core::stats::{CountingAllocator, Stats};
+use aoc_core::{OrExit, Solution};
+use dec15::Dec15;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut stats = Stats::from_args();
     let input = fs::read_to_string("input.txt").expect("Failed to read input!");
-    let input_parts: Vec<&str> = input.split("\n\n").collect();
-    let warehouse_input = input_parts[0];
-    let movements_input_raw = input_parts[1];
-    let movements_input = movements_input_raw.replace('\n', "");
-
-    let mut warehouse = Warehouse::new(warehouse_input.lines().collect());
-    let mut movements: Vec<Movement> = Vec::new();
-    for ch in movements_input.chars() {
-        movements.push(Movement::new(ch));
-    }
+    let parsed = stats.measure("parse", || Dec15::parse(&input).or_exit("input.txt"));
 
-    for movement in movements {
-        warehouse.move_robot(&movement);
-    }
+    let answer = stats.measure("part 1", || Dec15::part1(&parsed));
+    println!("pt1: {}", answer);
 
-    println!("pt1: {}", warehouse.gps_sum());
+    stats.report();
 }